@@ -8,7 +8,9 @@ use input::PlayerKeyboardInputPlugin;
 use main_menu::MainMenuPlugin;
 use maps::physics_platformer::PhysicsPlatformerPlugin;
 use modules::{
-    brain::BrainPlugin, character_controller::CharacterControllerPlugin, combat::CombatPlugin,
+    brain::BrainPlugin,
+    character_controller::{backend::rapier::RapierBackend, CharacterControllerPlugin},
+    combat::CombatPlugin,
     orbit_camera::OrbitCameraPlugin,
 };
 use startup::StartupPlugin;
@@ -36,6 +38,17 @@ macro_rules! get_single {
     };
 }
 
+/// Steps rapier once per `FixedUpdate` run, by the fixed timestep.
+fn configure_physics_timestep(
+    mut rapier_config: ResMut<RapierConfiguration>,
+    time: Res<Time<Fixed>>,
+) {
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: time.timestep().as_secs_f32(),
+        substeps: 1,
+    };
+}
+
 fn main() {
     App::new()
         .init_state::<AppState>()
@@ -44,18 +57,19 @@ fn main() {
             DefaultPlugins
                 .set(ImagePlugin::default_nearest())
                 .set(window_config::get_window_config()),
-            RapierPhysicsPlugin::<NoUserData>::default(),
+            RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
             RapierDebugRenderPlugin::default(),
             EguiPlugin,
             StartupPlugin,
             MainMenuPlugin,
-            BrainPlugin,
-            CombatPlugin,
+            BrainPlugin::new(FixedUpdate),
+            CombatPlugin::new(FixedUpdate),
             UiPlugin,
             OrbitCameraPlugin,
-            CharacterControllerPlugin::default(),
+            CharacterControllerPlugin::<RapierBackend>::new(FixedUpdate),
             PhysicsPlatformerPlugin,
             PlayerKeyboardInputPlugin,
         ))
+        .add_systems(Startup, configure_physics_timestep)
         .run();
 }
@@ -9,8 +9,13 @@ use crate::{
     get_single,
     modules::{
         brain::{JumpBrain, WanderingBrain},
-        character_controller::CharacterControllerBundle,
-        combat::combat_stats::StatsBundle,
+        character_controller::{
+            backend::{rapier::RapierCharacterBundle, SensorShape},
+            CharacterControllerBundle, GravitySource, GravityZone, InterpolatedVisual,
+            LandingRecovery, MovementTuningHandle, ProximitySensor, ProximitySensorCast,
+            TransformInterpolation, WaterVolume,
+        },
+        combat::{combat_stats::StatsBundle, fall_damage::FallDamage},
         orbit_camera::OrbitCamera,
     },
     mouse::{cursor_grab, cursor_release},
@@ -23,6 +28,14 @@ pub struct LevelZero(pub Handle<Mesh>);
 #[derive(Resource)]
 pub struct AssetsLoading(pub Vec<UntypedHandle>);
 
+/// Kinematic platform that reverses its linear velocity every `reverse_timer` period.
+#[derive(Component)]
+pub struct MovingPlatform {
+    pub linvel: Vec3,
+    pub angvel: Vec3,
+    pub reverse_timer: Timer,
+}
+
 fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
     let level_zero = asset_server.load("models/level1.glb#Mesh0/Primitive0");
     commands.insert_resource(LevelZero(level_zero));
@@ -30,6 +43,7 @@ fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
 
 fn setup_hero(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
@@ -47,24 +61,38 @@ fn setup_hero(
             Name::new("Hero"),
             Targetable,
             RigidBody::Dynamic,
-            LockedAxes::ROTATION_LOCKED_X | LockedAxes::ROTATION_LOCKED_Z,
             Collider::capsule_y(0.5, 1.),
-            CharacterControllerBundle::default(),
+            CharacterControllerBundle::default()
+                .with_proximity_sensor(ProximitySensor {
+                    origin: Vec3::Y * 0.4,
+                    cast: ProximitySensorCast::Shape(SensorShape::Ball(0.4)),
+                    ..default()
+                })
+                .with_body_radius(1.),
+            RapierCharacterBundle::default(),
+            TransformInterpolation::default(),
             TransformBundle::from(Transform::from_xyz(0.0, 5.0, 0.0)),
+            MovementTuningHandle(asset_server.load("movement/hero.movement.ron")),
             StatsBundle::default(),
+            FallDamage::default(),
+            LandingRecovery::default(),
         ))
         .with_children(|parent| {
-            parent.spawn(PbrBundle {
-                mesh,
-                material,
-                transform: Transform::default(),
-                ..default()
-            });
+            parent.spawn((
+                InterpolatedVisual,
+                PbrBundle {
+                    mesh,
+                    material,
+                    transform: Transform::default(),
+                    ..default()
+                },
+            ));
         });
 }
 
 fn setup_jump_brain(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
@@ -81,25 +109,31 @@ fn setup_jump_brain(
             Name::new("Jump Brain"),
             Targetable,
             RigidBody::Dynamic,
-            LockedAxes::ROTATION_LOCKED,
             Collider::capsule_y(0.5, 1.),
-            CharacterControllerBundle::default(),
+            CharacterControllerBundle::default().with_body_radius(1.),
+            RapierCharacterBundle::default(),
+            TransformInterpolation::default(),
             JumpBrain,
             TransformBundle::from(Transform::from_xyz(-10.0, 5.0, 0.0)),
+            MovementTuningHandle(asset_server.load("movement/brain.movement.ron")),
             StatsBundle::default(),
         ))
         .with_children(|parent| {
-            parent.spawn(PbrBundle {
-                mesh,
-                material,
-                transform: Transform::default(),
-                ..default()
-            });
+            parent.spawn((
+                InterpolatedVisual,
+                PbrBundle {
+                    mesh,
+                    material,
+                    transform: Transform::default(),
+                    ..default()
+                },
+            ));
         });
 }
 
 fn setup_wandering_brain(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
@@ -116,20 +150,25 @@ fn setup_wandering_brain(
             Name::new("Wandering Brain"),
             Targetable,
             RigidBody::Dynamic,
-            LockedAxes::ROTATION_LOCKED,
             Collider::capsule_y(0.5, 1.),
-            CharacterControllerBundle::default(),
+            CharacterControllerBundle::default().with_body_radius(1.),
+            RapierCharacterBundle::default(),
+            TransformInterpolation::default(),
             WanderingBrain,
             TransformBundle::from(Transform::from_xyz(-5.0, 5.0, 0.0)),
+            MovementTuningHandle(asset_server.load("movement/brain.movement.ron")),
             StatsBundle::default(),
         ))
         .with_children(|parent| {
-            parent.spawn(PbrBundle {
-                mesh,
-                material,
-                transform: Transform::default(),
-                ..default()
-            });
+            parent.spawn((
+                InterpolatedVisual,
+                PbrBundle {
+                    mesh,
+                    material,
+                    transform: Transform::default(),
+                    ..default()
+                },
+            ));
         });
 }
 
@@ -179,6 +218,167 @@ fn setup_world(
         });
 }
 
+fn setup_moving_platforms(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(6., 0.5, 6.));
+    let material: Handle<StandardMaterial> = materials.add(StandardMaterial {
+        base_color: Color::hex("#8fb996").unwrap(),
+        metallic: 0.5,
+        perceptual_roughness: 0.5,
+        ..default()
+    });
+
+    let platforms = [
+        (
+            "Sliding Platform",
+            Vec3::new(10.0, 1.0, 0.0),
+            Vec3::X * 3.,
+            Vec3::ZERO,
+        ),
+        (
+            "Elevator Platform",
+            Vec3::new(0.0, 1.0, 10.0),
+            Vec3::Y * 2.,
+            Vec3::ZERO,
+        ),
+        (
+            "Rotating Platform",
+            Vec3::new(-10.0, 1.0, 10.0),
+            Vec3::ZERO,
+            Vec3::Y * 0.5,
+        ),
+    ];
+
+    for (name, translation, linvel, angvel) in platforms {
+        commands.spawn((
+            Name::new(name),
+            MovingPlatform {
+                linvel,
+                angvel,
+                reverse_timer: Timer::from_seconds(4., TimerMode::Repeating),
+            },
+            RigidBody::KinematicVelocityBased,
+            Velocity { linvel, angvel },
+            Collider::cuboid(3., 0.25, 3.),
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(translation),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn setup_water(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let half_extents = Vec3::new(6., 2., 6.);
+
+    commands.spawn((
+        Name::new("Pool"),
+        WaterVolume { half_extents },
+        Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+        Sensor,
+        PbrBundle {
+            mesh: meshes.add(Cuboid::from_size(half_extents * 2.)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.2, 0.45, 0.8, 0.5),
+                alpha_mode: AlphaMode::Blend,
+                perceptual_roughness: 0.1,
+                ..default()
+            }),
+            transform: Transform::from_xyz(10.0, 2.0, -12.0),
+            ..default()
+        },
+    ));
+}
+
+fn setup_crates(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let half_extents = Vec3::splat(0.75);
+    let mesh = meshes.add(Cuboid::from_size(half_extents * 2.));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::hex("#a67c52").unwrap(),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+
+    for translation in [Vec3::new(4., 1., -6.), Vec3::new(6., 1., -6.)] {
+        commands.spawn((
+            Name::new("Crate"),
+            RigidBody::Dynamic,
+            Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            ColliderMassProperties::Density(0.5),
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(translation),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn setup_gravity(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let planet_radius = 4.;
+    commands.spawn((
+        Name::new("Planetoid"),
+        GravitySource {
+            strength: 9.81,
+            radius: 12.,
+        },
+        RigidBody::Fixed,
+        Collider::ball(planet_radius),
+        PbrBundle {
+            mesh: meshes.add(Sphere::new(planet_radius)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::hex("#b98f8f").unwrap(),
+                perceptual_roughness: 0.8,
+                ..default()
+            }),
+            transform: Transform::from_xyz(-20.0, 8.0, -20.0),
+            ..default()
+        },
+    ));
+
+    // a room with gravity pulling towards its +X wall
+    let half_extents = Vec3::new(4., 4., 4.);
+    commands.spawn((
+        Name::new("Sideways Gravity Zone"),
+        GravityZone {
+            gravity: Vec3::X * 9.81,
+        },
+        Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+        Sensor,
+        TransformBundle::from(Transform::from_xyz(-12.0, 4.0, 0.0)),
+    ));
+}
+
+fn move_platforms(time: Res<Time>, mut query: Query<(&mut MovingPlatform, &mut Velocity)>) {
+    for (mut platform, mut velocity) in query.iter_mut() {
+        platform.reverse_timer.tick(time.delta());
+        if platform.reverse_timer.just_finished() {
+            platform.linvel = -platform.linvel;
+        }
+
+        velocity.linvel = platform.linvel;
+        velocity.angvel = platform.angvel;
+    }
+}
+
 pub fn setup_player_camera(mut commands: Commands, player_query: Query<Entity, With<Player>>) {
     let player_entity = get_single!(player_query);
 
@@ -211,9 +411,14 @@ impl Plugin for PhysicsPlatformerPlugin {
                     setup_hero,
                     setup_wandering_brain,
                     setup_jump_brain,
+                    setup_moving_platforms,
+                    setup_water,
+                    setup_gravity,
+                    setup_crates,
                     setup_player_camera.after(setup_hero),
                 ),
             )
+            .add_systems(Update, move_platforms.run_if(in_state(AppState::Game)))
             .add_systems(OnExit(AppState::Game), cursor_release);
     }
 }
@@ -1,25 +1,16 @@
 use bevy::prelude::*;
 
-use crate::modules::character_controller::{
-    actions::JumpAction, CharacterController, WalkMotionType,
-};
+use crate::modules::character_controller::{CharacterController, MovementTuning};
 
 #[derive(Component)]
 pub struct JumpBrain;
 
-pub fn jump_brain_controller(mut brain_query: Query<&mut CharacterController, With<JumpBrain>>) {
-    for mut ctr in brain_query.iter_mut() {
-        ctr.motion_type(WalkMotionType {
-            velocity: Vec3::ZERO,
-            ..default()
-        });
-        ctr.action_type(JumpAction { velocity: Vec3::Y });
-    }
-}
-
-pub struct JumpBrainPlugin;
-impl Plugin for JumpBrainPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Update, jump_brain_controller);
+pub fn jump_brain_controller(
+    mut brain_query: Query<(&mut CharacterController, Option<&MovementTuning>), With<JumpBrain>>,
+) {
+    for (mut ctr, tuning) in brain_query.iter_mut() {
+        let tuning = tuning.copied().unwrap_or_default();
+        ctr.motion_type(tuning.walk(Vec3::ZERO, None));
+        ctr.action_type(tuning.jump());
     }
 }
@@ -1,5 +1,9 @@
-use bevy::prelude::*;
-pub struct BrainPlugin;
+use bevy::{
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    prelude::*,
+};
+
+use crate::modules::character_controller::UserControlsSystemSet;
 
 mod jump_brain;
 mod wandering_brain;
@@ -7,11 +11,34 @@ mod wandering_brain;
 pub use jump_brain::JumpBrain;
 pub use wandering_brain::WanderingBrain;
 
+/// Runs the brains in the same schedule as the character controller they feed.
+pub struct BrainPlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl BrainPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for BrainPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+
 impl Plugin for BrainPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((
-            wandering_brain::WanderingBrainPlugin,
-            jump_brain::JumpBrainPlugin,
-        ));
+        app.add_systems(
+            self.schedule,
+            (
+                wandering_brain::wandering_brain_controller,
+                jump_brain::jump_brain_controller,
+            )
+                .in_set(UserControlsSystemSet),
+        );
     }
 }
@@ -1,24 +1,18 @@
 use bevy::prelude::*;
 
-use crate::modules::character_controller::{CharacterController, WalkMotionType};
+use crate::modules::character_controller::{CharacterController, MovementTuning};
 
 #[derive(Component)]
 pub struct WanderingBrain;
 
 pub fn wandering_brain_controller(
-    mut brain_query: Query<&mut CharacterController, With<WanderingBrain>>,
+    mut brain_query: Query<
+        (&mut CharacterController, Option<&MovementTuning>),
+        With<WanderingBrain>,
+    >,
 ) {
-    for mut ctr in brain_query.iter_mut() {
-        ctr.motion_type(WalkMotionType {
-            velocity: Vec3::NEG_Z * 10.,
-            ..default()
-        })
-    }
-}
-
-pub struct WanderingBrainPlugin;
-impl Plugin for WanderingBrainPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Update, wandering_brain_controller);
+    for (mut ctr, tuning) in brain_query.iter_mut() {
+        let tuning = tuning.copied().unwrap_or_default();
+        ctr.motion_type(tuning.walk(Vec3::NEG_Z, None))
     }
 }
@@ -1,9 +1,10 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
-use bevy_rapier3d::pipeline::QueryFilter;
+use bevy::{prelude::*, time::Stopwatch};
 
-use crate::modules::character_controller::{motion::Motion, traits::action::*};
+use crate::modules::character_controller::{
+    backend::SpatialQueryFilter, motion::Motion, traits::action::*,
+};
 
 #[derive(Default, Debug)]
 pub enum AttackActionState {
@@ -37,20 +38,24 @@ impl Action for AttackAction {
                 let facing = ctx.transform.forward().normalize();
                 if timer.finished() {
                     // @todo attack logic
-                    let res = ctx.rapier_context.cast_ray(
+                    let res = ctx.spatial_query.cast_ray(
                         ctx.transform.translation + facing,
                         ctx.transform.forward().normalize(),
                         3.,
-                        false,
-                        QueryFilter::default(),
+                        SpatialQueryFilter {
+                            exclude_dynamic: false,
+                            exclude_sensors: false,
+                            ..default()
+                        },
+                        None,
                     );
 
                     match res {
                         None => {
                             info!("It hit nothing!");
                         }
-                        Some((e, distance)) => {
-                            info!("Hit on {:?} at distance {}", e, distance);
+                        Some(hit) => {
+                            info!("Hit on {:?} at distance {}", hit.entity, hit.distance);
                         }
                     }
 
@@ -74,7 +79,15 @@ impl Action for AttackAction {
         }
     }
 
-    fn initiation_decision(&self, _ctx: ActionContext) -> ActionInitiationDirective {
+    fn can_be_cancelled(&self, state: &Self::State) -> bool {
+        matches!(state, AttackActionState::Backswing(_))
+    }
+
+    fn initiation_decision(
+        &self,
+        _ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
         ActionInitiationDirective::Allow
     }
 }
@@ -1,8 +1,8 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, time::Stopwatch};
 
-use crate::modules::character_controller::{motion::*, traits::action::*};
+use crate::modules::character_controller::{motion::*, traits::action::*, ActionCooldownConfig};
 
 #[derive(Default, Debug)]
 pub enum DashActionState {
@@ -15,6 +15,22 @@ pub enum DashActionState {
 #[derive(Clone, Copy)]
 pub struct DashAction {
     pub facing: Direction3d,
+    /// Speed added along `facing` for the length of the dash.
+    pub speed: f32,
+    /// Seconds the dash lasts.
+    pub duration: f32,
+    pub cooldown: ActionCooldownConfig,
+}
+
+impl DashAction {
+    pub fn new(facing: Direction3d) -> Self {
+        Self {
+            facing,
+            speed: 10.,
+            duration: 0.3,
+            cooldown: ActionCooldownConfig::new(1., 2),
+        }
+    }
 }
 
 impl Action for DashAction {
@@ -31,8 +47,9 @@ impl Action for DashAction {
     ) -> ActionLifecycleDirective {
         match state {
             DashActionState::Started => {
-                *state = DashActionState::Active(Timer::from_seconds(0.3, TimerMode::Once));
-                motion.linvel += VelChange::impulse(Vec3::from(self.facing) * 10.);
+                *state =
+                    DashActionState::Active(Timer::from_seconds(self.duration, TimerMode::Once));
+                motion.linvel += VelChange::impulse(Vec3::from(self.facing) * self.speed);
                 ActionLifecycleDirective::Active
             }
             DashActionState::Active(timer) => {
@@ -44,13 +61,29 @@ impl Action for DashAction {
                 ActionLifecycleDirective::Active
             }
             DashActionState::Finished => {
-                motion.linvel += VelChange::boost(Vec3::from(self.facing) * -10.);
+                motion.linvel += VelChange::boost(Vec3::from(self.facing) * -self.speed);
                 ActionLifecycleDirective::Finished
             }
         }
     }
 
-    fn initiation_decision(&self, ctx: ActionContext) -> ActionInitiationDirective {
+    fn interrupt(&self, state: &mut Self::State, _ctx: ActionContext, motion: &mut Motion) {
+        // take back the dash speed, as if the dash had run its course
+        if let DashActionState::Active(_) = state {
+            motion.linvel += VelChange::boost(Vec3::from(self.facing) * -self.speed);
+        }
+        *state = DashActionState::Finished;
+    }
+
+    fn cooldown(&self) -> Option<ActionCooldownConfig> {
+        Some(self.cooldown)
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
         if ctx.motion_type.is_airborne() {
             ActionInitiationDirective::Allow
         } else {
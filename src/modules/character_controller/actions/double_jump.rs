@@ -0,0 +1,63 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{motion::*, traits::action::*};
+
+use super::{JumpAction, JumpActionState};
+
+/// A jump started in mid-air. Only a limited number are allowed before landing again.
+#[derive(Clone, Copy)]
+pub struct DoubleJumpAction {
+    pub jump: JumpAction,
+    /// Jumps allowed per airtime, on top of the one from the ground.
+    pub air_jumps: u32,
+}
+
+impl Default for DoubleJumpAction {
+    fn default() -> Self {
+        Self {
+            jump: JumpAction {
+                height: 1.5,
+                input_buffer_time: 0.,
+                ..default()
+            },
+            air_jumps: 1,
+        }
+    }
+}
+
+impl Action for DoubleJumpAction {
+    const NAME: &'static str = "DoubleJump";
+
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    /// Takes over a jump that is still rising instead of waiting for its apex.
+    const PRIORITY: i32 = 1;
+
+    type State = JumpActionState;
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: ActionContext,
+        lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        self.jump.apply(state, ctx, lifecycle, motion)
+    }
+
+    fn can_be_cancelled(&self, state: &Self::State) -> bool {
+        self.jump.can_be_cancelled(state)
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        if ctx.motion_type.is_airborne() && ctx.air_uses < self.air_jumps {
+            ActionInitiationDirective::Allow
+        } else {
+            ActionInitiationDirective::Reject
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{motion::*, traits::action::*};
+
+/// Slows the fall for as long as it is held.
+#[derive(Clone, Copy)]
+pub struct GlideAction {
+    pub max_fall_speed: f32,
+    /// Glides allowed per airtime. Letting go and gliding again counts as another one.
+    pub glides: u32,
+}
+
+impl Default for GlideAction {
+    fn default() -> Self {
+        Self {
+            max_fall_speed: 3.,
+            glides: 1,
+        }
+    }
+}
+
+impl Action for GlideAction {
+    const NAME: &'static str = "Glide";
+
+    type State = ();
+
+    fn apply(
+        &self,
+        _state: &mut Self::State,
+        ctx: ActionContext,
+        lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        if lifecycle == ActionLifecycle::NoLongerFed || !ctx.motion_type.is_airborne() {
+            return ActionLifecycleDirective::Finished;
+        }
+
+        let fall_speed = -ctx.velocity.linvel.dot(Vec3::from(ctx.up));
+        if fall_speed > self.max_fall_speed {
+            motion.linvel += VelChange::boost(ctx.up * (fall_speed - self.max_fall_speed));
+        }
+
+        ActionLifecycleDirective::Active
+    }
+
+    fn can_be_cancelled(&self, _state: &Self::State) -> bool {
+        true
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        let falling = ctx.velocity.linvel.dot(Vec3::from(ctx.up)) < 0.;
+        if ctx.motion_type.is_airborne() && falling && ctx.air_uses < self.glides {
+            ActionInitiationDirective::Allow
+        } else {
+            ActionInitiationDirective::Reject
+        }
+    }
+}
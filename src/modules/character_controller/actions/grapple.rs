@@ -0,0 +1,143 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{
+    backend::{RayHit, SpatialQueryFilter},
+    motion::*,
+    traits::action::*,
+    CharacterController,
+};
+
+#[derive(Default, Debug)]
+pub enum GrappleActionState {
+    #[default]
+    Started,
+    Attached {
+        anchor: Vec3,
+        /// Current rope length, shortened by reeling in.
+        length: f32,
+    },
+    Finished,
+}
+
+/// Shoots a rope along `aim` and hangs from where it hits, for as long as it is held.
+///
+/// The rope only pulls when it is stretched, so the character can swing under the anchor and
+/// gravity still applies. The rope reels in while attached, down to `min_length`.
+#[derive(Clone, Copy)]
+pub struct GrappleAction {
+    pub aim: Direction3d,
+    pub max_range: f32,
+    /// Colliders the rope can attach to. Only fixed bodies by default, since the anchor stays
+    /// where the rope hit and would not follow a moving one. Excluding dynamic bodies also keeps
+    /// the rope from hitting the character itself.
+    pub filter: SpatialQueryFilter,
+    /// Acceleration per unit the rope is stretched.
+    pub stiffness: f32,
+    /// Damping of the speed at which the character moves away from the anchor.
+    pub damping: f32,
+    pub reel_speed: f32,
+    pub min_length: f32,
+}
+
+impl GrappleAction {
+    pub fn new(aim: Direction3d) -> Self {
+        Self {
+            aim,
+            max_range: 25.,
+            filter: SpatialQueryFilter {
+                exclude_kinematic: true,
+                ..default()
+            },
+            stiffness: 60.,
+            damping: 8.,
+            reel_speed: 6.,
+            min_length: 2.,
+        }
+    }
+
+    fn cast(&self, ctx: &ActionContext) -> Option<RayHit> {
+        ctx.spatial_query.cast_ray(
+            ctx.transform.translation,
+            self.aim.into(),
+            self.max_range,
+            self.filter,
+            None,
+        )
+    }
+}
+
+impl Action for GrappleAction {
+    const NAME: &'static str = "Grapple";
+
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    type State = GrappleActionState;
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: ActionContext,
+        lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        if lifecycle == ActionLifecycle::NoLongerFed {
+            *state = GrappleActionState::Finished;
+        }
+        if let GrappleActionState::Started = state {
+            *state = match self.cast(&ctx) {
+                Some(hit) => GrappleActionState::Attached {
+                    anchor: hit.point,
+                    length: hit.distance,
+                },
+                // the aim moved off the target between initiation and now
+                None => GrappleActionState::Finished,
+            };
+        }
+        let GrappleActionState::Attached { anchor, length } = state else {
+            return ActionLifecycleDirective::Finished;
+        };
+
+        *length = (*length - self.reel_speed * ctx.frame_duration).max(self.min_length);
+
+        let to_anchor = *anchor - ctx.transform.translation;
+        let stretch = to_anchor.length() - *length;
+        if stretch > 0. {
+            let along = to_anchor.normalize_or_zero();
+            let moving_away = (-ctx.velocity.linvel.dot(along)).max(0.);
+            motion.linvel +=
+                VelChange::accel(along * (stretch * self.stiffness + moving_away * self.damping));
+        }
+
+        ActionLifecycleDirective::Active
+    }
+
+    fn can_be_cancelled(&self, _state: &Self::State) -> bool {
+        true
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        if self.cast(&ctx).is_some() {
+            ActionInitiationDirective::Allow
+        } else {
+            ActionInitiationDirective::Reject
+        }
+    }
+}
+
+/// Draws the rope of every character hanging from a grapple.
+pub fn grapple_rope_system(
+    mut gizmos: Gizmos,
+    query: Query<(&GlobalTransform, &CharacterController)>,
+) {
+    for (transform, ctr) in query.iter() {
+        if let Some((_, GrappleActionState::Attached { anchor, .. })) =
+            ctr.action::<GrappleAction>()
+        {
+            gizmos.line(transform.translation(), *anchor, Color::BISQUE);
+        }
+    }
+}
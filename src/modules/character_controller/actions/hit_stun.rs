@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{motion::*, traits::action::*};
+
+#[derive(Default, Debug)]
+pub enum HitStunActionState {
+    #[default]
+    Started,
+    Stunned(Timer),
+    Finished,
+}
+
+/// Takes control away from the character for a moment after being hit.
+#[derive(Clone, Copy)]
+pub struct HitStunAction {
+    pub duration: f32,
+    pub knockback: Vec3,
+}
+
+impl Default for HitStunAction {
+    fn default() -> Self {
+        Self {
+            duration: 0.4,
+            knockback: Vec3::ZERO,
+        }
+    }
+}
+
+impl Action for HitStunAction {
+    const NAME: &'static str = "HitStun";
+
+    const PRIORITY: i32 = 100;
+
+    type State = HitStunActionState;
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: ActionContext,
+        _lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        let up = Vec3::from(ctx.up);
+        match state {
+            HitStunActionState::Started => {
+                *state = HitStunActionState::Stunned(Timer::from_seconds(
+                    self.duration,
+                    TimerMode::Once,
+                ));
+                motion.linvel += VelChange::impulse(self.knockback);
+                ActionLifecycleDirective::Active
+            }
+            HitStunActionState::Stunned(timer) => {
+                if timer.finished() {
+                    *state = HitStunActionState::Finished;
+                } else {
+                    timer.tick(Duration::from_secs_f32(ctx.frame_duration));
+                    // drop the steering of the basis, keeping only what holds the character up
+                    motion.linvel.accel = motion.linvel.accel.project_onto(up);
+                    motion.linvel.boost = motion.linvel.boost.project_onto(up);
+                    motion.angvel = VelChange::boost(-ctx.velocity.angvel);
+                }
+                ActionLifecycleDirective::Active
+            }
+            HitStunActionState::Finished => ActionLifecycleDirective::Finished,
+        }
+    }
+
+    fn initiation_decision(
+        &self,
+        _ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        ActionInitiationDirective::Allow
+    }
+}
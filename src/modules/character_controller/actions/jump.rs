@@ -1,71 +1,176 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, time::Stopwatch};
 
-use crate::modules::character_controller::{motion::*, traits::action::*, WalkMotionType};
+use crate::modules::character_controller::{motion::*, traits::action::*, SwimBasis};
 
 #[derive(Default, Debug)]
 pub enum JumpActionState {
     #[default]
     Started,
-    Active(Timer),
+    Rising {
+        origin: Vec3,
+    },
+    Hang(Timer),
+    Falling,
     Finished,
 }
 
 #[derive(Clone, Copy)]
 pub struct JumpAction {
-    pub velocity: Vec3,
+    /// Height reached at the apex when the jump is held all the way up.
+    pub height: f32,
+    /// Height reached even when the jump is released right away.
+    pub min_height: f32,
+    /// Extra downward acceleration once the jump is released above `min_height`.
+    pub shorten_extra_gravity: f32,
+    /// Extra downward acceleration while falling. When zero the action finishes at the apex.
+    pub fall_extra_gravity: f32,
+    /// Seconds spent floating at the apex while the jump is still held.
+    pub peak_hang_time: f32,
+    /// Seconds a jump pressed in mid-air is kept around, waiting for the character to land.
+    pub input_buffer_time: f32,
 }
 
 impl Default for JumpAction {
     fn default() -> Self {
         Self {
-            velocity: Default::default(),
+            height: 2.0,
+            min_height: 0.5,
+            shorten_extra_gravity: 40.,
+            fall_extra_gravity: 0.,
+            peak_hang_time: 0.1,
+            input_buffer_time: 0.2,
         }
     }
 }
 
+impl JumpAction {
+    fn takeoff_speed(&self, gravity: f32) -> f32 {
+        (2. * gravity * self.height).sqrt()
+    }
+
+    fn after_apex(&self, lifecycle: ActionLifecycle) -> JumpActionState {
+        if lifecycle == ActionLifecycle::StillFed && self.peak_hang_time > 0. {
+            JumpActionState::Hang(Timer::from_seconds(self.peak_hang_time, TimerMode::Once))
+        } else {
+            JumpActionState::Falling
+        }
+    }
+
+    /// Under water the jump turns into swimming up for as long as it is held.
+    fn swim_up(
+        &self,
+        swim: &SwimBasis,
+        state: &mut JumpActionState,
+        ctx: ActionContext,
+        lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        if lifecycle == ActionLifecycle::NoLongerFed {
+            *state = JumpActionState::Finished;
+            return ActionLifecycleDirective::Finished;
+        }
+
+        let upward_velocity = ctx.velocity.linvel.dot(Vec3::from(ctx.up));
+        motion.linvel += VelChange::boost(ctx.up * (swim.swim_up_speed - upward_velocity).max(0.));
+        ActionLifecycleDirective::Active
+    }
+}
+
 impl Action for JumpAction {
     const NAME: &'static str = "Jump";
 
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    type State = JumpActionState;
+
     fn apply(
         &self,
         state: &mut Self::State,
         ctx: ActionContext,
-        _lifecycle: ActionLifecycle,
+        lifecycle: ActionLifecycle,
         motion: &mut Motion,
     ) -> ActionLifecycleDirective {
-        let current_motion_type = ctx.concrete_motion_type::<WalkMotionType>();
-        if let Some((_, motion_state)) = current_motion_type {
-            let boost = (Vec3::Y * -motion_state.spring_force).max(Vec3::ZERO);
-            motion.linvel += VelChange::boost(boost);
+        if let Some((swim, swim_state)) = ctx.concrete_motion_type::<SwimBasis>() {
+            // at the surface the jump leaps out of the water like on land
+            if !swim_state.surfaced {
+                return self.swim_up(swim, state, ctx, lifecycle, motion);
+            }
         }
 
+        let up = Vec3::from(ctx.up);
+        let gravity = ctx.gravity.length();
+        let upward_velocity = ctx.velocity.linvel.dot(up);
+
         match state {
             JumpActionState::Started => {
-                motion.linvel += VelChange::impulse(Vec3::Y * 5.);
-                *state = JumpActionState::Active(Timer::from_seconds(0.75, TimerMode::Once));
+                let ground_upward_velocity = ctx
+                    .proximity_sensor_output
+                    .map(|output| output.ground_velocity.linvel.dot(up))
+                    .unwrap_or(0.);
+                let boost = self.takeoff_speed(gravity) + ground_upward_velocity - upward_velocity;
+                motion.linvel += VelChange::boost(up * boost);
+                *state = JumpActionState::Rising {
+                    origin: ctx.transform.translation,
+                };
                 ActionLifecycleDirective::Active
             }
-            JumpActionState::Active(timer) => {
-                if timer.finished() {
-                    *state = JumpActionState::Finished;
+            JumpActionState::Rising { origin } => {
+                if upward_velocity <= 0. {
+                    *state = self.after_apex(lifecycle);
+                    return ActionLifecycleDirective::Active;
+                }
+
+                let climbed = (ctx.transform.translation - *origin).dot(up);
+                if lifecycle == ActionLifecycle::NoLongerFed && climbed >= self.min_height {
+                    motion.linvel += VelChange::accel(-up * self.shorten_extra_gravity);
+                }
+                ActionLifecycleDirective::Active
+            }
+            JumpActionState::Hang(timer) => {
+                if timer.finished() || lifecycle == ActionLifecycle::NoLongerFed {
+                    *state = JumpActionState::Falling;
                 } else {
                     timer.tick(Duration::from_secs_f32(ctx.frame_duration));
+                    motion.linvel +=
+                        VelChange::accel(-ctx.gravity) + VelChange::boost(-up * upward_velocity);
                 }
                 ActionLifecycleDirective::Active
             }
+            JumpActionState::Falling => {
+                if self.fall_extra_gravity <= 0. {
+                    *state = JumpActionState::Finished;
+                    return ActionLifecycleDirective::Finished;
+                }
+
+                if !ctx.motion_type.is_airborne() {
+                    *state = JumpActionState::Finished;
+                    ActionLifecycleDirective::Finished
+                } else {
+                    motion.linvel += VelChange::accel(-up * self.fall_extra_gravity);
+                    ActionLifecycleDirective::Active
+                }
+            }
             JumpActionState::Finished => ActionLifecycleDirective::Finished,
         }
     }
 
-    type State = JumpActionState;
+    fn can_be_cancelled(&self, state: &Self::State) -> bool {
+        matches!(state, JumpActionState::Hang(_) | JumpActionState::Falling)
+    }
 
-    fn initiation_decision(&self, ctx: ActionContext) -> ActionInitiationDirective {
-        if ctx.motion_type.is_airborne() {
-            ActionInitiationDirective::Reject
-        } else {
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        if !ctx.motion_type.is_airborne() {
             ActionInitiationDirective::Allow
+        } else if being_fed_for.elapsed_secs() < self.input_buffer_time {
+            ActionInitiationDirective::Delay
+        } else {
+            ActionInitiationDirective::Reject
         }
     }
 }
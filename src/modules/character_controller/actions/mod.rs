@@ -1,7 +1,21 @@
 mod attack;
 mod dash;
+mod double_jump;
+mod glide;
+mod grapple;
+mod hit_stun;
 mod jump;
+mod scripted_motion;
+mod wall_jump;
+mod wall_slide;
 
 pub use attack::*;
 pub use dash::*;
+pub use double_jump::*;
+pub use glide::*;
+pub use grapple::*;
+pub use hit_stun::*;
 pub use jump::*;
+pub use scripted_motion::*;
+pub use wall_jump::*;
+pub use wall_slide::*;
@@ -0,0 +1,184 @@
+use bevy::{math::cubic_splines::CubicCurve, prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{motion::*, traits::action::*};
+
+/// Offsets from where the motion starts, passed through evenly over the duration.
+#[derive(Debug, Clone)]
+pub struct MotionProfile {
+    points: Vec<Vec3>,
+}
+
+impl MotionProfile {
+    /// A straight move by `displacement`.
+    pub fn displacement(displacement: Vec3) -> Self {
+        Self {
+            points: vec![Vec3::ZERO, displacement],
+        }
+    }
+
+    /// Follows `curve`, sampled `subdivisions` times, relative to its first point.
+    pub fn curve(curve: &CubicCurve<Vec3>, subdivisions: usize) -> Self {
+        let points: Vec<Vec3> = curve.iter_positions(subdivisions.max(1)).collect();
+        let start = points.first().copied().unwrap_or(Vec3::ZERO);
+        Self {
+            points: points.into_iter().map(|point| point - start).collect(),
+        }
+    }
+
+    /// Offset at `t`, from 0 at the start to 1 at the end.
+    pub fn offset(&self, t: f32) -> Vec3 {
+        let Some(last) = self.points.len().checked_sub(1).filter(|last| *last > 0) else {
+            return self.points.first().copied().unwrap_or(Vec3::ZERO);
+        };
+        let scaled = t.clamp(0., 1.) * last as f32;
+        let i = (scaled.floor() as usize).min(last - 1);
+        self.points[i].lerp(self.points[i + 1], scaled - i as f32)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptedMotionMode {
+    /// Only drives movement along the ground plane. The basis keeps the character on the ground.
+    #[default]
+    Grounded,
+    /// Drives movement in all directions, ignoring the basis and gravity.
+    Flying,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MotionSpace {
+    World,
+    /// Offsets are rotated by the character's rotation when the motion starts.
+    #[default]
+    Character,
+}
+
+#[derive(Default, Debug)]
+pub enum ScriptedMotionActionState {
+    #[default]
+    Started,
+    Moving {
+        origin: Vec3,
+        rotation: Quat,
+        elapsed: f32,
+    },
+    Finished,
+}
+
+/// Moves the character along a profile over time, for lunges, knockbacks and scripted walks.
+///
+/// The motion is driven through velocity, so collisions still stop the character. It runs to the
+/// end once started, even if it is no longer fed.
+#[derive(Clone)]
+pub struct ScriptedMotionAction {
+    pub profile: MotionProfile,
+    pub duration: f32,
+    pub mode: ScriptedMotionMode,
+    pub space: MotionSpace,
+    /// Speed the character never exceeds, including while catching up after being blocked.
+    pub max_speed: f32,
+}
+
+impl ScriptedMotionAction {
+    pub fn new(profile: MotionProfile, duration: f32) -> Self {
+        Self {
+            profile,
+            duration,
+            mode: ScriptedMotionMode::Grounded,
+            space: MotionSpace::Character,
+            max_speed: 30.,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: ScriptedMotionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_space(mut self, space: MotionSpace) -> Self {
+        self.space = space;
+        self
+    }
+}
+
+impl Action for ScriptedMotionAction {
+    const NAME: &'static str = "ScriptedMotion";
+
+    type State = ScriptedMotionActionState;
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: ActionContext,
+        _lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        if let ScriptedMotionActionState::Started = state {
+            *state = ScriptedMotionActionState::Moving {
+                origin: ctx.transform.translation,
+                rotation: match self.space {
+                    MotionSpace::World => Quat::IDENTITY,
+                    MotionSpace::Character => ctx.transform.rotation,
+                },
+                elapsed: 0.,
+            };
+        }
+        let ScriptedMotionActionState::Moving {
+            origin,
+            rotation,
+            elapsed,
+        } = state
+        else {
+            return ActionLifecycleDirective::Finished;
+        };
+
+        *elapsed += ctx.frame_duration;
+        let t = match self.duration > 0. {
+            true => *elapsed / self.duration,
+            false => 1.,
+        };
+        let target = *origin + *rotation * self.profile.offset(t);
+        let desired_velocity = ((target - ctx.transform.translation)
+            / ctx.frame_duration.max(f32::EPSILON))
+        .clamp_length_max(self.max_speed);
+        let finished = t >= 1.;
+
+        let up = Vec3::from(ctx.up);
+        match self.mode {
+            ScriptedMotionMode::Grounded => {
+                let delta = match finished {
+                    // hand the character back to the basis without the leftover speed
+                    true => -ctx.velocity.linvel.reject_from(up),
+                    false => (desired_velocity - ctx.velocity.linvel).reject_from(up),
+                };
+                motion.linvel = VelChange {
+                    accel: motion.linvel.accel.project_onto(up),
+                    boost: motion.linvel.boost.project_onto(up) + delta,
+                    impulse: motion.linvel.impulse,
+                };
+            }
+            ScriptedMotionMode::Flying => {
+                let delta = match finished {
+                    true => -ctx.velocity.linvel,
+                    false => desired_velocity - ctx.velocity.linvel,
+                };
+                motion.linvel = VelChange::boost(delta) + VelChange::accel(-ctx.gravity);
+            }
+        }
+
+        if finished {
+            *state = ScriptedMotionActionState::Finished;
+            ActionLifecycleDirective::Finished
+        } else {
+            ActionLifecycleDirective::Active
+        }
+    }
+
+    fn initiation_decision(
+        &self,
+        _ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        ActionInitiationDirective::Allow
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{motion::*, traits::action::*};
+
+#[derive(Default, Debug)]
+pub enum WallJumpActionState {
+    #[default]
+    Started,
+    Pushing(Timer),
+    Finished,
+}
+
+/// Kicks off the wall the character is touching, away from its normal.
+#[derive(Clone, Copy)]
+pub struct WallJumpAction {
+    pub height: f32,
+    pub push_speed: f32,
+    /// Seconds after the kick during which the push is not steered against.
+    pub push_time: f32,
+}
+
+impl Default for WallJumpAction {
+    fn default() -> Self {
+        Self {
+            height: 2.,
+            push_speed: 8.,
+            push_time: 0.2,
+        }
+    }
+}
+
+impl Action for WallJumpAction {
+    const NAME: &'static str = "WallJump";
+
+    const VIOLATES_COYOTE_TIME: bool = true;
+
+    type State = WallJumpActionState;
+
+    fn apply(
+        &self,
+        state: &mut Self::State,
+        ctx: ActionContext,
+        _lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        let up = Vec3::from(ctx.up);
+        match state {
+            WallJumpActionState::Started => {
+                let away = ctx
+                    .wall_sensor_output
+                    .map(|wall| wall.normal.reject_from(up).normalize_or_zero())
+                    .unwrap_or(Vec3::ZERO);
+                let takeoff_speed = (2. * ctx.gravity.length() * self.height).sqrt();
+
+                let current = ctx.velocity.linvel;
+                let target = away * self.push_speed + up * takeoff_speed;
+                motion.linvel += VelChange::boost(target - current);
+
+                *state = WallJumpActionState::Pushing(Timer::from_seconds(
+                    self.push_time,
+                    TimerMode::Once,
+                ));
+                ActionLifecycleDirective::Active
+            }
+            WallJumpActionState::Pushing(timer) => {
+                if timer.finished() {
+                    *state = WallJumpActionState::Finished;
+                } else {
+                    timer.tick(Duration::from_secs_f32(ctx.frame_duration));
+                    // undo the air control of the walk basis while the push plays out
+                    motion.linvel = VelChange {
+                        accel: motion.linvel.accel.project_onto(up),
+                        boost: motion.linvel.boost.project_onto(up),
+                        impulse: motion.linvel.impulse,
+                    };
+                }
+                ActionLifecycleDirective::Active
+            }
+            WallJumpActionState::Finished => ActionLifecycleDirective::Finished,
+        }
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        if ctx.motion_type.is_airborne() && ctx.wall_sensor_output.is_some() {
+            ActionInitiationDirective::Allow
+        } else {
+            ActionInitiationDirective::Reject
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::modules::character_controller::{motion::*, traits::action::*};
+
+/// Caps the fall speed while the character is pressed against a wall.
+#[derive(Clone, Copy)]
+pub struct WallSlideAction {
+    pub max_fall_speed: f32,
+}
+
+impl Default for WallSlideAction {
+    fn default() -> Self {
+        Self { max_fall_speed: 2. }
+    }
+}
+
+impl Action for WallSlideAction {
+    const NAME: &'static str = "WallSlide";
+
+    type State = ();
+
+    fn apply(
+        &self,
+        _state: &mut Self::State,
+        ctx: ActionContext,
+        lifecycle: ActionLifecycle,
+        motion: &mut Motion,
+    ) -> ActionLifecycleDirective {
+        let Some(wall) = ctx.wall_sensor_output else {
+            return ActionLifecycleDirective::Finished;
+        };
+        if lifecycle == ActionLifecycle::NoLongerFed || !ctx.motion_type.is_airborne() {
+            return ActionLifecycleDirective::Finished;
+        }
+
+        let fall_speed = -ctx.velocity.linvel.dot(Vec3::from(ctx.up));
+        if fall_speed > self.max_fall_speed {
+            motion.linvel += VelChange::boost(ctx.up * (fall_speed - self.max_fall_speed));
+        }
+        // keep in contact with the wall
+        motion.linvel += VelChange::accel(-wall.normal);
+
+        ActionLifecycleDirective::Active
+    }
+
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        _being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        let falling = ctx.velocity.linvel.dot(Vec3::from(ctx.up)) < 0.;
+        if ctx.motion_type.is_airborne() && ctx.wall_sensor_output.is_some() && falling {
+            ActionInitiationDirective::Allow
+        } else {
+            ActionInitiationDirective::Reject
+        }
+    }
+}
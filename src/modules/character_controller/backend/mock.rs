@@ -0,0 +1,274 @@
+use bevy::{
+    ecs::{
+        schedule::InternedScheduleLabel,
+        system::{SystemParam, SystemParamItem},
+    },
+    prelude::*,
+};
+
+use crate::modules::character_controller::{
+    gravity::gravity_system, motion::Motion, CharacterControllerPipelineStages,
+};
+
+use super::{
+    CharacterControllerBackend, RayHit, RigidBodyTracker, RigidBodyVelocity, SensorShape, ShapeHit,
+    SpatialQuery, SpatialQueryFilter,
+};
+
+/// A deterministic stand-in for a physics engine, for driving the controller without one.
+///
+/// The world is a list of axis-aligned boxes in [`MockWorld`]. Bodies with [`MockRigidBody`]
+/// integrate the motors' output with a fixed order of operations and never collide.
+pub struct MockBackend;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MockCollider {
+    pub entity: Entity,
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub velocity: RigidBodyVelocity,
+    pub dynamic: bool,
+    pub kinematic: bool,
+    pub sensor: bool,
+}
+
+impl MockCollider {
+    fn passes(
+        &self,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> bool {
+        !(filter.exclude_dynamic && self.dynamic)
+            && !(filter.exclude_kinematic && self.kinematic)
+            && !(filter.exclude_sensors && self.sensor)
+            && filter.exclude_body != Some(self.entity)
+            && predicate.map_or(true, |predicate| predicate(self.entity))
+    }
+
+    /// Slab test of a ray against the box grown by `margin`.
+    fn cast_ray(&self, origin: Vec3, direction: Vec3, margin: Vec3) -> Option<(f32, Vec3)> {
+        let min = self.center - self.half_extents - margin;
+        let max = self.center + self.half_extents + margin;
+
+        let mut entry = f32::NEG_INFINITY;
+        let mut exit = f32::INFINITY;
+        let mut normal = Vec3::ZERO;
+        for axis in 0..3 {
+            if direction[axis].abs() < f32::EPSILON {
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let near = (min[axis] - origin[axis]) / direction[axis];
+            let far = (max[axis] - origin[axis]) / direction[axis];
+            let (near, far) = (near.min(far), near.max(far));
+            if near > entry {
+                entry = near;
+                normal = Vec3::ZERO;
+                normal[axis] = -direction[axis].signum();
+            }
+            exit = exit.min(far);
+        }
+
+        (entry <= exit && exit >= 0.).then_some((entry.max(0.), normal))
+    }
+}
+
+/// Static description of the world the mock backend queries.
+#[derive(Debug, Default, Resource)]
+pub struct MockWorld {
+    pub gravity: Vec3,
+    pub colliders: Vec<MockCollider>,
+}
+
+/// A body moved by the mock backend. `half_extents` is its bounding box for intersections.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct MockRigidBody {
+    pub velocity: RigidBodyVelocity,
+    pub half_extents: Vec3,
+}
+
+#[derive(SystemParam)]
+pub struct MockSpatialQuery<'w, 's> {
+    world: Res<'w, MockWorld>,
+    bodies: Query<'w, 's, (&'static Transform, &'static MockRigidBody)>,
+}
+
+impl MockSpatialQuery<'_, '_> {
+    fn closest_hit(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        margin: Vec3,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<(&MockCollider, f32, Vec3)> {
+        self.world
+            .colliders
+            .iter()
+            .filter(|collider| collider.passes(filter, predicate))
+            .filter_map(|collider| {
+                let (distance, normal) = collider.cast_ray(origin, direction, margin)?;
+                (distance <= max_distance).then_some((collider, distance, normal))
+            })
+            .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+    }
+}
+
+impl SpatialQuery for MockSpatialQuery<'_, '_> {
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<RayHit> {
+        let direction = direction.normalize_or_zero();
+        self.closest_hit(
+            origin,
+            direction,
+            max_distance,
+            Vec3::ZERO,
+            filter,
+            predicate,
+        )
+        .map(|(collider, distance, normal)| RayHit {
+            entity: collider.entity,
+            distance,
+            point: origin + direction * distance,
+            normal,
+        })
+    }
+
+    /// Casts a ray against boxes grown by the shape's bounding box. Rotation is ignored.
+    fn cast_shape(
+        &self,
+        shape: SensorShape,
+        origin: Vec3,
+        _rotation: Quat,
+        direction: Vec3,
+        max_distance: f32,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<ShapeHit> {
+        let margin = match shape {
+            SensorShape::Ball(radius) => Vec3::splat(radius),
+            SensorShape::Capsule {
+                half_height,
+                radius,
+            } => Vec3::new(radius, half_height + radius, radius),
+            SensorShape::Cuboid(half_extents) => half_extents,
+        };
+        self.closest_hit(
+            origin,
+            direction.normalize_or_zero(),
+            max_distance,
+            margin,
+            filter,
+            predicate,
+        )
+        .map(|(collider, distance, _)| ShapeHit {
+            entity: collider.entity,
+            distance,
+        })
+    }
+
+    fn rigid_body_of(&self, collider: Entity) -> Entity {
+        collider
+    }
+
+    fn point_velocity(&self, collider: Entity, point: Vec3) -> RigidBodyVelocity {
+        match self
+            .world
+            .colliders
+            .iter()
+            .find(|other| other.entity == collider)
+        {
+            Some(other) => RigidBodyVelocity {
+                linvel: other.velocity.linvel + other.velocity.angvel.cross(point - other.center),
+                angvel: other.velocity.angvel,
+            },
+            None => RigidBodyVelocity::ZERO,
+        }
+    }
+
+    fn intersections_with(&self, body: Entity) -> Vec<Entity> {
+        let Ok((transform, rigid_body)) = self.bodies.get(body) else {
+            return Vec::new();
+        };
+
+        self.world
+            .colliders
+            .iter()
+            .filter(|collider| {
+                let offset = (collider.center - transform.translation).abs();
+                offset
+                    .cmple(collider.half_extents + rigid_body.half_extents)
+                    .all()
+            })
+            .map(|collider| collider.entity)
+            .collect()
+    }
+
+    fn gravity(&self) -> Vec3 {
+        self.world.gravity
+    }
+}
+
+pub fn mock_tracker_system(mut query: Query<(&mut RigidBodyTracker, &MockRigidBody)>) {
+    for (mut tracker, rigid_body) in query.iter_mut() {
+        tracker.velocity = rigid_body.velocity;
+    }
+}
+
+/// Integrates `Motion` with unit mass: boosts and impulses first, then accelerations and gravity
+/// over the frame, then the position.
+pub fn mock_motor_system(
+    time: Res<Time>,
+    mut query: Query<(
+        &Motion,
+        &RigidBodyTracker,
+        &mut MockRigidBody,
+        &mut Transform,
+    )>,
+) {
+    let dt = time.delta_seconds();
+    for (motion, tracker, mut rigid_body, mut transform) in query.iter_mut() {
+        let velocity = &mut rigid_body.velocity;
+        velocity.linvel += motion.linvel.boost + motion.linvel.impulse;
+        velocity.linvel += (motion.linvel.accel + tracker.gravity) * dt;
+        velocity.angvel += motion.angvel.boost + motion.angvel.impulse;
+        velocity.angvel += motion.angvel.accel * dt;
+
+        transform.translation += velocity.linvel * dt;
+        transform.rotation = Quat::from_scaled_axis(velocity.angvel * dt) * transform.rotation;
+    }
+}
+
+impl CharacterControllerBackend for MockBackend {
+    type SpatialQuery = MockSpatialQuery<'static, 'static>;
+
+    fn spatial_query<'a>(
+        param: &'a SystemParamItem<'_, '_, Self::SpatialQuery>,
+    ) -> &'a dyn SpatialQuery {
+        param
+    }
+
+    fn build(app: &mut App, schedule: InternedScheduleLabel) {
+        app.init_resource::<MockWorld>();
+        app.add_systems(
+            schedule,
+            mock_tracker_system
+                .before(gravity_system::<Self>)
+                .in_set(CharacterControllerPipelineStages::Sensors),
+        );
+        app.add_systems(
+            schedule,
+            mock_motor_system.in_set(CharacterControllerPipelineStages::Motors),
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::{
+    ecs::{
+        schedule::InternedScheduleLabel,
+        system::{SystemParam, SystemParamItem},
+    },
+    prelude::*,
+};
+
+/// Only used to run the controller under test, without a physics engine.
+#[cfg(test)]
+pub mod mock;
+pub mod rapier;
+
+/// Velocity of a rigid body, independent of the physics backend.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RigidBodyVelocity {
+    pub linvel: Vec3,
+    pub angvel: Vec3,
+}
+
+impl RigidBodyVelocity {
+    pub const ZERO: Self = Self {
+        linvel: Vec3::ZERO,
+        angvel: Vec3::ZERO,
+    };
+}
+
+/// State of the character's rigid body, copied from the backend before the sensors run.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct RigidBodyTracker {
+    pub velocity: RigidBodyVelocity,
+    /// Gravity acting on this character, after gravity zones and sources.
+    pub gravity: Vec3,
+}
+
+impl RigidBodyTracker {
+    /// The character's local up, opposite to its gravity.
+    pub fn up(&self) -> Direction3d {
+        Direction3d::new(-self.gravity).unwrap_or(Direction3d::Y)
+    }
+
+    /// Rotation from world space, where up is Y, to the character's gravity frame.
+    pub fn gravity_frame(&self) -> Quat {
+        Quat::from_rotation_arc(Vec3::Y, Vec3::from(self.up()))
+    }
+}
+
+/// Shapes the sensors can sweep through the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorShape {
+    Ball(f32),
+    /// A capsule along Y.
+    Capsule {
+        half_height: f32,
+        radius: f32,
+    },
+    Cuboid(Vec3),
+}
+
+/// Which colliders a spatial query can hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialQueryFilter {
+    pub exclude_dynamic: bool,
+    /// Skips bodies moved by code rather than physics, e.g. moving platforms.
+    pub exclude_kinematic: bool,
+    pub exclude_sensors: bool,
+    /// Memberships and filter bits, as in rapier's `CollisionGroups`.
+    pub collision_groups: Option<(u32, u32)>,
+    /// Body whose colliders are ignored, usually the character itself.
+    pub exclude_body: Option<Entity>,
+}
+
+impl Default for SpatialQueryFilter {
+    fn default() -> Self {
+        Self {
+            exclude_dynamic: true,
+            exclude_kinematic: false,
+            exclude_sensors: true,
+            collision_groups: None,
+            exclude_body: None,
+        }
+    }
+}
+
+impl SpatialQueryFilter {
+    pub fn excluding(self, body: Entity) -> Self {
+        Self {
+            exclude_body: Some(body),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub entity: Entity,
+    pub distance: f32,
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShapeHit {
+    pub entity: Entity,
+    pub distance: f32,
+}
+
+/// Queries the sensors and actions run against the physics world.
+pub trait SpatialQuery {
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<RayHit>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn cast_shape(
+        &self,
+        shape: SensorShape,
+        origin: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_distance: f32,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<ShapeHit>;
+
+    /// Rigid body a collider is attached to.
+    fn rigid_body_of(&self, collider: Entity) -> Entity;
+
+    /// Velocity of the body owning `collider` at `point`, together with its angular velocity.
+    fn point_velocity(&self, collider: Entity, point: Vec3) -> RigidBodyVelocity;
+
+    /// Colliders currently overlapping the colliders of `body`.
+    fn intersections_with(&self, body: Entity) -> Vec<Entity>;
+
+    /// Gravity of the physics world, used where no gravity zone or source applies.
+    fn gravity(&self) -> Vec3;
+}
+
+/// Connects the character controller to a physics engine.
+///
+/// The backend fills the velocity of [`RigidBodyTracker`] during the Sensors stage, answers the spatial queries
+/// of the sensors and actions, and turns `Motion` plus the tracked gravity into forces during the
+/// Motors stage.
+pub trait CharacterControllerBackend: 'static + Send + Sync {
+    type SpatialQuery: SystemParam + 'static;
+
+    fn spatial_query<'a>(
+        param: &'a SystemParamItem<'_, '_, Self::SpatialQuery>,
+    ) -> &'a dyn SpatialQuery;
+
+    /// Adds the tracker and motor systems to `schedule`.
+    fn build(app: &mut App, schedule: InternedScheduleLabel);
+}
@@ -0,0 +1,290 @@
+use bevy::{
+    ecs::{
+        schedule::InternedScheduleLabel,
+        system::{SystemParam, SystemParamItem},
+    },
+    prelude::*,
+};
+use bevy_rapier3d::prelude::*;
+
+use crate::modules::character_controller::{
+    gravity::gravity_system, motion::Motion, CharacterController,
+    CharacterControllerPipelineStages, CharacterControllerSystemSet,
+};
+
+use super::{
+    CharacterControllerBackend, RayHit, RigidBodyTracker, RigidBodyVelocity, SensorShape, ShapeHit,
+    SpatialQuery, SpatialQueryFilter,
+};
+
+/// Runs the character controller on bevy_rapier3d.
+pub struct RapierBackend;
+
+/// Rapier components the motors write to. Add it next to `CharacterControllerBundle`.
+///
+/// Rapier's own gravity is disabled, the motor applies the character's tracked gravity instead.
+#[derive(Bundle)]
+pub struct RapierCharacterBundle {
+    velocity: Velocity,
+    external_force: ExternalForce,
+    impulse: ExternalImpulse,
+    read_mass_properties: ReadMassProperties,
+    gravity_scale: GravityScale,
+}
+
+impl Default for RapierCharacterBundle {
+    fn default() -> Self {
+        Self {
+            velocity: default(),
+            external_force: default(),
+            impulse: default(),
+            read_mass_properties: default(),
+            gravity_scale: GravityScale(0.),
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct RapierSpatialQuery<'w, 's> {
+    rapier_context: Res<'w, RapierContext>,
+    rapier_config: Res<'w, RapierConfiguration>,
+    bodies: Query<'w, 's, (&'static Velocity, &'static GlobalTransform)>,
+}
+
+fn query_filter<'a>(
+    filter: SpatialQueryFilter,
+    predicate: Option<&'a dyn Fn(Entity) -> bool>,
+) -> QueryFilter<'a> {
+    let mut flags = QueryFilterFlags::empty();
+    flags.set(QueryFilterFlags::EXCLUDE_DYNAMIC, filter.exclude_dynamic);
+    flags.set(
+        QueryFilterFlags::EXCLUDE_KINEMATIC,
+        filter.exclude_kinematic,
+    );
+    flags.set(QueryFilterFlags::EXCLUDE_SENSORS, filter.exclude_sensors);
+
+    QueryFilter {
+        flags,
+        groups: filter.collision_groups.map(|(memberships, filter)| {
+            CollisionGroups::new(
+                Group::from_bits_truncate(memberships),
+                Group::from_bits_truncate(filter),
+            )
+        }),
+        exclude_rigid_body: filter.exclude_body,
+        predicate,
+        ..default()
+    }
+}
+
+fn collider(shape: SensorShape) -> Collider {
+    match shape {
+        SensorShape::Ball(radius) => Collider::ball(radius),
+        SensorShape::Capsule {
+            half_height,
+            radius,
+        } => Collider::capsule_y(half_height, radius),
+        SensorShape::Cuboid(half_extents) => {
+            Collider::cuboid(half_extents.x, half_extents.y, half_extents.z)
+        }
+    }
+}
+
+impl SpatialQuery for RapierSpatialQuery<'_, '_> {
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<RayHit> {
+        self.rapier_context
+            .cast_ray_and_get_normal(
+                origin,
+                direction,
+                max_distance,
+                false,
+                query_filter(filter, predicate),
+            )
+            .map(|(entity, intersection)| RayHit {
+                entity,
+                distance: intersection.toi,
+                point: intersection.point,
+                normal: intersection.normal,
+            })
+    }
+
+    fn cast_shape(
+        &self,
+        shape: SensorShape,
+        origin: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_distance: f32,
+        filter: SpatialQueryFilter,
+        predicate: Option<&dyn Fn(Entity) -> bool>,
+    ) -> Option<ShapeHit> {
+        self.rapier_context
+            .cast_shape(
+                origin,
+                rotation,
+                direction,
+                &collider(shape),
+                max_distance,
+                true,
+                query_filter(filter, predicate),
+            )
+            .map(|(entity, toi)| ShapeHit {
+                entity,
+                distance: toi.toi,
+            })
+    }
+
+    fn rigid_body_of(&self, collider: Entity) -> Entity {
+        self.rapier_context
+            .collider_parent(collider)
+            .unwrap_or(collider)
+    }
+
+    fn point_velocity(&self, collider: Entity, point: Vec3) -> RigidBodyVelocity {
+        let body = self.rigid_body_of(collider);
+        if let Ok((velocity, transform)) = self.bodies.get(body) {
+            return RigidBodyVelocity {
+                linvel: velocity.linvel + velocity.angvel.cross(point - transform.translation()),
+                angvel: velocity.angvel,
+            };
+        }
+
+        // bodies without a `Velocity` component, e.g. dynamic props, only move in rapier
+        self.rapier_context
+            .entity2body()
+            .get(&body)
+            .and_then(|handle| self.rapier_context.bodies.get(*handle))
+            .map_or(RigidBodyVelocity::ZERO, |body| RigidBodyVelocity {
+                linvel: body.velocity_at_point(&point.into()).into(),
+                angvel: (*body.angvel()).into(),
+            })
+    }
+
+    fn intersections_with(&self, body: Entity) -> Vec<Entity> {
+        self.rapier_context
+            .intersection_pairs_with(body)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .map(|(collider1, collider2, _)| {
+                if collider1 == body {
+                    collider2
+                } else {
+                    collider1
+                }
+            })
+            .collect()
+    }
+
+    fn gravity(&self) -> Vec3 {
+        self.rapier_config.gravity
+    }
+}
+
+pub fn rapier_tracker_system(mut query: Query<(&mut RigidBodyTracker, &Velocity)>) {
+    for (mut tracker, velocity) in query.iter_mut() {
+        tracker.velocity = RigidBodyVelocity {
+            linvel: velocity.linvel,
+            angvel: velocity.angvel,
+        };
+    }
+}
+
+pub fn rapier_motor_system(
+    mut query: Query<(
+        &Motion,
+        &RigidBodyTracker,
+        &mut ExternalForce,
+        &mut Velocity,
+        &mut ExternalImpulse,
+        &ReadMassProperties,
+    )>,
+) {
+    for (motion, tracker, mut force, mut velocity, mut impulse, mass_properties) in query.iter_mut()
+    {
+        let mass = mass_properties.get().mass;
+        velocity.linvel += motion.linvel.boost;
+        velocity.angvel += motion.angvel.boost;
+
+        force.force = (motion.linvel.accel + tracker.gravity) * mass;
+        force.torque = motion.angvel.accel * mass;
+
+        impulse.impulse = motion.linvel.impulse * mass;
+        impulse.torque_impulse = motion.angvel.impulse * mass;
+    }
+}
+
+/// Pushes the dynamic body a character stands on with the force that holds the character up,
+/// so its weight rests on that body.
+pub fn rapier_ground_reaction_system(
+    time: Res<Time>,
+    mut rapier_context: ResMut<RapierContext>,
+    query: Query<(
+        &CharacterController,
+        &Motion,
+        &RigidBodyTracker,
+        &ReadMassProperties,
+    )>,
+) {
+    for (ctr, motion, tracker, mass_properties) in query.iter() {
+        let Some(ground) = ctr.ground() else {
+            continue;
+        };
+        let up = Vec3::from(tracker.up());
+        let support = (motion.linvel.boost + motion.linvel.accel * time.delta_seconds()).dot(up);
+        if support <= 0. {
+            continue;
+        }
+
+        let body_entity = rapier_context
+            .collider_parent(ground.entity)
+            .unwrap_or(ground.entity);
+        let Some(handle) = rapier_context.entity2body().get(&body_entity).copied() else {
+            continue;
+        };
+        let Some(body) = rapier_context.bodies.get_mut(handle) else {
+            continue;
+        };
+        if !body.is_dynamic() {
+            continue;
+        }
+
+        let impulse = -up * support * mass_properties.get().mass;
+        body.apply_impulse_at_point(impulse.into(), ground.point.into(), true);
+    }
+}
+
+impl CharacterControllerBackend for RapierBackend {
+    type SpatialQuery = RapierSpatialQuery<'static, 'static>;
+
+    fn spatial_query<'a>(
+        param: &'a SystemParamItem<'_, '_, Self::SpatialQuery>,
+    ) -> &'a dyn SpatialQuery {
+        param
+    }
+
+    fn build(app: &mut App, schedule: InternedScheduleLabel) {
+        // only matters when rapier steps in the same schedule, e.g. `in_fixed_schedule()`
+        app.configure_sets(
+            schedule,
+            CharacterControllerSystemSet.before(PhysicsSet::SyncBackend),
+        );
+        app.add_systems(
+            schedule,
+            rapier_tracker_system
+                // gravity and the sensors chained after it see this step's velocity
+                .before(gravity_system::<Self>)
+                .in_set(CharacterControllerPipelineStages::Sensors),
+        );
+        app.add_systems(
+            schedule,
+            (rapier_motor_system, rapier_ground_reaction_system)
+                .in_set(CharacterControllerPipelineStages::Motors),
+        );
+    }
+}
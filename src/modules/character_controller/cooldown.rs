@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::world3d::Player;
+
+use super::CharacterController;
+
+/// How often an action can be started, as returned by `Action::cooldown`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionCooldownConfig {
+    /// Seconds it takes a spent charge to refill.
+    pub duration: f32,
+    /// How many times the action can be started back to back.
+    pub charges: u32,
+}
+
+impl ActionCooldownConfig {
+    pub fn new(duration: f32, charges: u32) -> Self {
+        Self { duration, charges }
+    }
+}
+
+/// Charges of a single named action. Every spent charge refills on its own timer.
+#[derive(Debug)]
+pub struct ActionCooldown {
+    config: ActionCooldownConfig,
+    refilling: Vec<Timer>,
+}
+
+impl ActionCooldown {
+    pub(crate) fn new(config: ActionCooldownConfig) -> Self {
+        Self {
+            config,
+            refilling: Vec::new(),
+        }
+    }
+
+    pub fn max_charges(&self) -> u32 {
+        self.config.charges
+    }
+
+    pub fn charges(&self) -> u32 {
+        self.config
+            .charges
+            .saturating_sub(self.refilling.len() as u32)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.charges() > 0
+    }
+
+    /// Seconds until the next charge refills, or `None` if all charges are full.
+    pub fn time_to_next_charge(&self) -> Option<f32> {
+        self.refilling
+            .iter()
+            .map(|timer| timer.remaining_secs())
+            .min_by(f32::total_cmp)
+    }
+
+    pub(crate) fn configure(&mut self, config: ActionCooldownConfig) {
+        self.config = config;
+    }
+
+    pub(crate) fn spend(&mut self) {
+        if self.is_ready() {
+            self.refilling
+                .push(Timer::from_seconds(self.config.duration, TimerMode::Once));
+        }
+    }
+
+    pub(crate) fn tick(&mut self, delta: Duration) {
+        for timer in self.refilling.iter_mut() {
+            timer.tick(delta);
+        }
+        self.refilling.retain(|timer| !timer.finished());
+    }
+}
+
+pub fn debug_cooldown_system(
+    mut contexts: EguiContexts,
+    query: Query<&CharacterController, With<Player>>,
+) {
+    for ctr in query.iter() {
+        egui::Window::new("Player cooldowns").show(contexts.ctx_mut(), |ui| {
+            for (name, cooldown) in ctr.cooldowns() {
+                ui.label(format!(
+                    "{name}: {}/{} charges, next in {:.2}s",
+                    cooldown.charges(),
+                    cooldown.max_charges(),
+                    cooldown.time_to_next_charge().unwrap_or(0.)
+                ));
+            }
+        });
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+/// A contender action was started and became the current action.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ActionStartedEvent {
+    pub entity: Entity,
+    pub name: &'static str,
+}
+
+/// The current action ran its course.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ActionFinishedEvent {
+    pub entity: Entity,
+    pub name: &'static str,
+}
+
+/// The current action was cut short by a contender with a higher priority, or during a cancel
+/// window.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ActionInterruptedEvent {
+    pub entity: Entity,
+    pub name: &'static str,
+    pub interrupted_by: &'static str,
+}
+
+/// Why a contender action was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionRejectionReason {
+    /// Its `initiation_decision` rejected it.
+    Declined,
+    /// It has no cooldown charges left.
+    Cooldown,
+}
+
+/// A contender action was refused. Sent once while the action keeps being fed, and again if it is
+/// refused for another reason or fed anew.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ActionRejectedEvent {
+    pub entity: Entity,
+    pub name: &'static str,
+    pub reason: ActionRejectionReason,
+}
+
+/// The current basis changed, either because it was fed for the first time or because the
+/// controller switched to a stashed one.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BasisChangedEvent {
+    pub entity: Entity,
+    pub from: Option<&'static str>,
+    pub to: &'static str,
+}
+
+/// The character touched the ground again after being airborne.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LandedEvent {
+    pub entity: Entity,
+    /// Seconds the character spent in the air.
+    pub airtime: f32,
+    pub impact_velocity: Vec3,
+    /// Speed into the ground, along the character's up.
+    pub impact_speed: f32,
+}
@@ -0,0 +1,59 @@
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use super::backend::{CharacterControllerBackend, RigidBodyTracker};
+
+/// A volume with its own gravity. Needs a sensor collider so the backend reports the characters
+/// inside it. Overrides gravity sources and the world gravity.
+#[derive(Debug, Component)]
+pub struct GravityZone {
+    pub gravity: Vec3,
+}
+
+/// Pulls characters within `radius` towards its center, like a small planet.
+#[derive(Debug, Component)]
+pub struct GravitySource {
+    /// Acceleration towards the center.
+    pub strength: f32,
+    pub radius: f32,
+}
+
+/// Works out the gravity acting on each character. Characters inside a gravity zone take its
+/// gravity, the strongest one if zones overlap. Otherwise the pull of the gravity sources in range
+/// adds up, and with none in range the world gravity applies.
+pub fn gravity_system<B: CharacterControllerBackend>(
+    mut q: Query<(Entity, &mut RigidBodyTracker, &Transform)>,
+    zones: Query<&GravityZone>,
+    sources: Query<(&GravitySource, &GlobalTransform)>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
+) {
+    let spatial_query = B::spatial_query(&spatial_query);
+
+    for (entity, mut tracker, transform) in q.iter_mut() {
+        let zone_gravity = spatial_query
+            .intersections_with(entity)
+            .into_iter()
+            .filter_map(|other| zones.get(other).ok())
+            .map(|zone| zone.gravity)
+            .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+        if let Some(gravity) = zone_gravity {
+            tracker.gravity = gravity;
+            continue;
+        }
+
+        let mut in_range = false;
+        let source_gravity = sources
+            .iter()
+            .filter_map(|(source, source_transform)| {
+                let offset = source_transform.translation() - transform.translation;
+                (offset.length() <= source.radius).then_some((source, offset))
+            })
+            .inspect(|_| in_range = true)
+            .map(|(source, offset)| offset.normalize_or_zero() * source.strength)
+            .sum::<Vec3>();
+
+        tracker.gravity = match in_range {
+            true => source_gravity,
+            false => spatial_query.gravity(),
+        };
+    }
+}
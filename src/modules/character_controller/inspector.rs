@@ -0,0 +1,227 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use bevy::{asset::ron, prelude::*};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::world3d::Player;
+
+use super::{
+    backend::RigidBodyTracker, motion::Motion, CharacterController, MovementTuning,
+    MovementTuningHandle, WalkMotionType,
+};
+
+/// Samples kept for the plots.
+const HISTORY_LENGTH: usize = 300;
+
+#[derive(Default)]
+pub struct TuningInspector {
+    selected: Option<Entity>,
+    /// Speed along the ground plane and along up, and the walk spring force, oldest first.
+    history: VecDeque<[f32; 3]>,
+    export_status: Option<String>,
+}
+
+impl TuningInspector {
+    fn record(&mut self, sample: [f32; 3]) {
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(sample);
+    }
+}
+
+fn plot(ui: &mut egui::Ui, label: &str, samples: impl Iterator<Item = f32> + Clone) {
+    let max = samples
+        .clone()
+        .fold(1., |max: f32, sample| max.max(sample.abs()));
+    ui.label(format!("{label} (±{max:.1})"));
+
+    let (response, painter) =
+        ui.allocate_painter(egui::vec2(ui.available_width(), 60.), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_stroke(rect, 0., egui::Stroke::new(1., egui::Color32::DARK_GRAY));
+    painter.hline(
+        rect.x_range(),
+        rect.center().y,
+        egui::Stroke::new(1., egui::Color32::DARK_GRAY),
+    );
+
+    let step = rect.width() / (HISTORY_LENGTH - 1) as f32;
+    let points = samples
+        .enumerate()
+        .map(|(i, sample)| {
+            egui::pos2(
+                rect.left() + i as f32 * step,
+                rect.center().y - sample / max * rect.height() * 0.5,
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.5, egui::Color32::LIGHT_BLUE),
+    ));
+}
+
+fn tuning_editor(ui: &mut egui::Ui, tuning: &mut MovementTuning) {
+    ui.collapsing("Walk", |ui| {
+        let walk = &mut tuning.walk;
+        ui.add(egui::Slider::new(&mut walk.speed, 0. ..=40.).text("speed"));
+        ui.add(egui::Slider::new(&mut walk.floating_height, 0.5..=4.).text("floating height"));
+        ui.add(egui::Slider::new(&mut walk.turning_angvel, 0. ..=20.).text("turning angvel"));
+        ui.add(egui::Slider::new(&mut walk.spring_strength, 0. ..=50.).text("spring strength"));
+        ui.add(egui::Slider::new(&mut walk.spring_damper, 0. ..=5.).text("spring damper"));
+        ui.add(egui::Slider::new(&mut walk.coyote_time, 0. ..=1.).text("coyote time"));
+        ui.add(egui::Slider::new(&mut walk.air_acceleration, 0. ..=100.).text("air acceleration"));
+        ui.add(egui::Slider::new(&mut walk.air_deceleration, 0. ..=100.).text("air deceleration"));
+        ui.add(egui::Slider::new(&mut walk.max_air_speed, 0. ..=40.).text("max air speed"));
+        ui.add(egui::Slider::new(&mut walk.max_step_height, 0. ..=2.).text("max step height"));
+        ui.add(egui::Slider::new(&mut walk.snap_distance, 0. ..=2.).text("snap distance"));
+    });
+    ui.collapsing("Jump", |ui| {
+        let jump = &mut tuning.jump;
+        ui.add(egui::Slider::new(&mut jump.height, 0. ..=10.).text("height"));
+        ui.add(egui::Slider::new(&mut jump.min_height, 0. ..=10.).text("min height"));
+        ui.add(
+            egui::Slider::new(&mut jump.shorten_extra_gravity, 0. ..=100.)
+                .text("shorten extra gravity"),
+        );
+        ui.add(
+            egui::Slider::new(&mut jump.fall_extra_gravity, 0. ..=100.).text("fall extra gravity"),
+        );
+        ui.add(egui::Slider::new(&mut jump.peak_hang_time, 0. ..=1.).text("peak hang time"));
+        ui.add(egui::Slider::new(&mut jump.input_buffer_time, 0. ..=1.).text("input buffer time"));
+    });
+    ui.collapsing("Dash", |ui| {
+        let dash = &mut tuning.dash;
+        ui.add(egui::Slider::new(&mut dash.speed, 0. ..=40.).text("speed"));
+        ui.add(egui::Slider::new(&mut dash.duration, 0. ..=2.).text("duration"));
+        ui.add(egui::Slider::new(&mut dash.cooldown, 0. ..=10.).text("cooldown"));
+        ui.add(egui::Slider::new(&mut dash.charges, 1..=5).text("charges"));
+    });
+}
+
+/// Where exported tuning is written: the file the character's asset was loaded from, or a new
+/// file named after the character.
+fn export_path(handle: Option<&MovementTuningHandle>, name: &str) -> PathBuf {
+    let asset_path = handle
+        .and_then(|handle| handle.0.path())
+        .map(|path| path.path().to_path_buf())
+        .unwrap_or_else(|| {
+            PathBuf::from("movement").join(format!("{}.movement.ron", name.to_lowercase()))
+        });
+    PathBuf::from("assets").join(asset_path)
+}
+
+fn export(tuning: &MovementTuning, path: &PathBuf) -> Result<(), String> {
+    let text = ron::ser::to_string_pretty(tuning, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|err| err.to_string())
+}
+
+/// Shows the motion of a character and edits its movement tuning live. Exported tuning is
+/// hot-reloaded from disk like any other edit to the file.
+#[allow(clippy::type_complexity)]
+pub fn tuning_inspector_system(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut inspector: Local<TuningInspector>,
+    mut query: Query<(
+        Entity,
+        Option<&Name>,
+        Has<Player>,
+        &CharacterController,
+        &Motion,
+        &RigidBodyTracker,
+        Option<&mut MovementTuning>,
+        Option<&MovementTuningHandle>,
+    )>,
+) {
+    if inspector
+        .selected
+        .map_or(true, |entity| !query.contains(entity))
+    {
+        inspector.selected = query
+            .iter()
+            .find(|(_, _, is_player, ..)| *is_player)
+            .map(|(entity, ..)| entity);
+        inspector.history.clear();
+    }
+
+    let label = |entity: Entity, name: Option<&Name>| {
+        name.map_or_else(|| format!("{entity:?}"), |name| name.to_string())
+    };
+
+    egui::Window::new("Movement tuning").show(contexts.ctx_mut(), |ui| {
+        let mut selected = inspector.selected;
+        egui::ComboBox::from_label("Character")
+            .selected_text(
+                selected
+                    .and_then(|entity| query.get(entity).ok())
+                    .map_or_else(String::new, |(entity, name, ..)| label(entity, name)),
+            )
+            .show_ui(ui, |ui| {
+                for (entity, name, ..) in query.iter() {
+                    ui.selectable_value(&mut selected, Some(entity), label(entity, name));
+                }
+            });
+        if selected != inspector.selected {
+            inspector.selected = selected;
+            inspector.history.clear();
+            inspector.export_status = None;
+        }
+
+        let Some(Ok((entity, name, _, ctr, motion, tracker, tuning, handle))) =
+            selected.map(|entity| query.get_mut(entity))
+        else {
+            ui.label("No character controller to inspect");
+            return;
+        };
+
+        let up = Vec3::from(tracker.up());
+        let spring_force = ctr
+            .basis::<WalkMotionType>()
+            .map_or(0., |(_, state)| state.spring_force);
+        inspector.record([
+            tracker.velocity.linvel.reject_from(up).length(),
+            tracker.velocity.linvel.dot(up),
+            spring_force,
+        ]);
+
+        ui.label(format!("basis: {}", ctr.basis_name().unwrap_or("none")));
+        ui.label(format!("action: {}", ctr.action_name().unwrap_or("none")));
+        ui.label(format!("motion::linvel {:?}", motion.linvel));
+        ui.label(format!("motion::angvel {:?}", motion.angvel));
+        ui.label(format!("gravity {:?}", tracker.gravity));
+
+        plot(
+            ui,
+            "horizontal speed",
+            inspector.history.iter().map(|s| s[0]),
+        );
+        plot(ui, "vertical speed", inspector.history.iter().map(|s| s[1]));
+        plot(ui, "spring force", inspector.history.iter().map(|s| s[2]));
+
+        ui.separator();
+        let Some(mut tuning) = tuning else {
+            if ui.button("Add movement tuning").clicked() {
+                commands.entity(entity).insert(MovementTuning::default());
+            }
+            return;
+        };
+        tuning_editor(ui, &mut tuning);
+
+        let path = export_path(handle, &label(entity, name));
+        if ui.button(format!("Export to {}", path.display())).clicked() {
+            inspector.export_status = Some(match export(&tuning, &path) {
+                Ok(()) => format!("Exported to {}", path.display()),
+                Err(err) => format!("Export failed: {err}"),
+            });
+        }
+        if let Some(status) = &inspector.export_status {
+            ui.label(status);
+        }
+    });
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+/// Smooths out a body that moves in a fixed timestep.
+///
+/// Stores the body's last two fixed-step transforms. Children marked with [`InterpolatedVisual`]
+/// are placed between them every frame, according to how far the fixed clock has run past the
+/// last step.
+#[derive(Debug, Default, Component)]
+pub struct TransformInterpolation {
+    previous: Option<Transform>,
+    current: Option<Transform>,
+}
+
+impl TransformInterpolation {
+    /// Transform between the last two fixed steps, `overstep` being `Time<Fixed>`'s
+    /// `overstep_fraction`.
+    pub fn interpolated(&self, overstep: f32) -> Option<Transform> {
+        let current = self.current?;
+        let previous = self.previous.unwrap_or(current);
+        Some(Transform {
+            translation: previous.translation.lerp(current.translation, overstep),
+            rotation: previous.rotation.slerp(current.rotation, overstep),
+            scale: previous.scale.lerp(current.scale, overstep),
+        })
+    }
+}
+
+/// A child that renders the interpolated transform of its [`TransformInterpolation`] parent.
+#[derive(Debug, Default, Component)]
+pub struct InterpolatedVisual;
+
+/// Runs after every fixed step, once the physics backend wrote back the transforms.
+pub fn record_transform_interpolation_system(
+    mut query: Query<(&mut TransformInterpolation, &Transform)>,
+) {
+    for (mut interpolation, transform) in query.iter_mut() {
+        interpolation.previous = interpolation.current.or(Some(*transform));
+        interpolation.current = Some(*transform);
+    }
+}
+
+pub fn interpolate_visuals_system(
+    time: Res<Time<Fixed>>,
+    bodies: Query<(&TransformInterpolation, &Transform, &Children)>,
+    mut visuals: Query<&mut Transform, (With<InterpolatedVisual>, Without<TransformInterpolation>)>,
+) {
+    let overstep = time.overstep_fraction();
+    for (interpolation, transform, children) in bodies.iter() {
+        let Some(interpolated) = interpolation.interpolated(overstep) else {
+            continue;
+        };
+
+        // the body itself sits at the latest step, so offset the visual back from there
+        let inverse_rotation = transform.rotation.inverse();
+        let mut visual_iter = visuals.iter_many_mut(children.iter());
+        while let Some(mut visual) = visual_iter.fetch_next() {
+            visual.translation =
+                inverse_rotation * (interpolated.translation - transform.translation);
+            visual.rotation = inverse_rotation * interpolated.rotation;
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+/// Briefly locks a character out of moving and starting actions after a hard landing.
+///
+/// Actions fed during the lockout wait for it to end rather than being rejected, so a buffered
+/// jump still goes off.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct LandingRecovery {
+    /// Speed into the ground from which a landing counts as hard.
+    pub min_impact_speed: f32,
+    /// Seconds the lockout lasts.
+    pub duration: f32,
+}
+
+impl Default for LandingRecovery {
+    fn default() -> Self {
+        Self {
+            min_impact_speed: 14.,
+            duration: 0.3,
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+
+use super::{
+    backend::RigidBodyTracker,
+    ledge_sensor::{LedgeSensor, LedgeSensorOutput},
+    motion::{Motion, VelChange},
+    traits::basis::{Basis, BasisContext},
+    utils::ProjectionPlaneForRotation,
+    CharacterController, WalkMotionType,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LedgeHangPhase {
+    /// Moving from where the ledge was grabbed to the hanging position.
+    #[default]
+    Reaching,
+    Hanging,
+    ClimbingUp,
+    ClimbingOver,
+    /// Let go of the ledge. The controller switches back to walking.
+    Released,
+}
+
+#[derive(Debug, Default)]
+pub struct LedgeHangState {
+    pub phase: LedgeHangPhase,
+    pub ledge: Option<LedgeSensorOutput>,
+    /// When the character last let go, in seconds since startup, for `regrab_cooldown`.
+    pub released_at: Option<f32>,
+}
+
+#[derive(Copy, Clone)]
+pub struct LedgeHangBasis {
+    /// Climb up onto the ledge.
+    pub climb: bool,
+    /// Let go of the ledge.
+    pub drop: bool,
+    /// How far below the top of the ledge the character hangs.
+    pub hang_depth: f32,
+    /// How far from the wall the character hangs.
+    pub hang_distance: f32,
+    pub climb_speed: f32,
+    /// Height above the ledge the character is lifted to before stepping onto it.
+    pub climb_height: f32,
+    /// How far past the edge the character steps when climbing over.
+    pub climb_forward: f32,
+    pub turning_angvel: f32,
+    /// Seconds after letting go before a ledge can be grabbed again.
+    pub regrab_cooldown: f32,
+}
+
+impl Default for LedgeHangBasis {
+    fn default() -> Self {
+        Self {
+            climb: false,
+            drop: false,
+            hang_depth: 1.2,
+            hang_distance: 0.6,
+            climb_speed: 4.,
+            climb_height: 2.0,
+            climb_forward: 0.8,
+            turning_angvel: 5.,
+            regrab_cooldown: 0.4,
+        }
+    }
+}
+
+impl LedgeHangBasis {
+    fn target(&self, phase: LedgeHangPhase, ledge: &LedgeSensorOutput, up: Vec3) -> Vec3 {
+        match phase {
+            LedgeHangPhase::ClimbingUp => {
+                ledge.point + up * self.climb_height + ledge.wall_normal * self.hang_distance
+            }
+            LedgeHangPhase::ClimbingOver => {
+                ledge.point + up * self.climb_height - ledge.wall_normal * self.climb_forward
+            }
+            _ => ledge.point - up * self.hang_depth + ledge.wall_normal * self.hang_distance,
+        }
+    }
+
+    fn get_torque(&self, ctx: BasisContext, wall_normal: Vec3) -> f32 {
+        let projection = ProjectionPlaneForRotation::from_up_using_default_forward(ctx.up);
+        let current_forward = ctx.transform.rotation.mul_vec3(projection.forward);
+        let rotation_along_up_axis =
+            projection.rotation_to_set_forward(current_forward, -wall_normal);
+
+        (rotation_along_up_axis / ctx.frame_duration)
+            .clamp(-self.turning_angvel, self.turning_angvel)
+            - ctx.velocity.angvel.dot(Vec3::from(ctx.up))
+    }
+}
+
+impl Basis for LedgeHangBasis {
+    const NAME: &'static str = "LedgeHang";
+
+    type State = LedgeHangState;
+
+    fn apply(&self, state: &mut Self::State, ctx: BasisContext, motion: &mut Motion) {
+        if state.ledge.is_none() {
+            state.ledge = ctx.ledge_sensor_output;
+        }
+        if state.ledge.is_none() || (self.drop && state.phase == LedgeHangPhase::Hanging) {
+            state.phase = LedgeHangPhase::Released;
+        }
+        let Some(ledge) = state
+            .ledge
+            .filter(|_| state.phase != LedgeHangPhase::Released)
+        else {
+            motion.linvel = VelChange::ZERO;
+            motion.angvel = VelChange::ZERO;
+            return;
+        };
+
+        let up = Vec3::from(ctx.up);
+        let offset = self.target(state.phase, &ledge, up) - ctx.transform.translation;
+        let reached = offset.length() < 0.05;
+        state.phase = match state.phase {
+            LedgeHangPhase::Reaching if reached => LedgeHangPhase::Hanging,
+            LedgeHangPhase::Hanging if self.climb => LedgeHangPhase::ClimbingUp,
+            LedgeHangPhase::ClimbingUp if reached => LedgeHangPhase::ClimbingOver,
+            LedgeHangPhase::ClimbingOver if reached => LedgeHangPhase::Released,
+            phase => phase,
+        };
+
+        // close the gap over a few frames, but never faster than climb_speed
+        let desired_velocity = (offset / ctx.frame_duration.max(f32::EPSILON) * 0.25)
+            .clamp_length_max(self.climb_speed);
+        motion.linvel = VelChange::boost(desired_velocity - ctx.velocity.linvel)
+            + VelChange::accel(-ctx.gravity);
+        motion.angvel = VelChange::boost(up * self.get_torque(ctx, ledge.wall_normal));
+    }
+
+    fn is_airborne(&self, state: &Self::State) -> bool {
+        state.phase == LedgeHangPhase::Released
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State) {
+        state.phase = LedgeHangPhase::Released;
+    }
+
+    fn displacement(&self, _state: &Self::State) -> Option<Vec3> {
+        None
+    }
+}
+
+/// Switches falling characters onto a ledge in front of them, and back to walking once they let go.
+pub fn ledge_grab_system(
+    time: Res<Time>,
+    mut query: Query<(&mut CharacterController, &LedgeSensor, &RigidBodyTracker)>,
+) {
+    for (mut ctr, ledge_sensor, tracker) in query.iter_mut() {
+        let Some(basis_name) = ctr.basis_name() else {
+            continue;
+        };
+
+        if basis_name == LedgeHangBasis::NAME {
+            let released = ctr
+                .basis_mut::<LedgeHangBasis>(LedgeHangBasis::NAME)
+                .is_some_and(|ledge_hang| ledge_hang.state.phase == LedgeHangPhase::Released);
+            if released && ctr.switch_basis(WalkMotionType::NAME) {
+                // the stashed hang basis keeps its state, so the release time lives with the entity
+                if let Some(ledge_hang) = ctr.basis_mut::<LedgeHangBasis>(LedgeHangBasis::NAME) {
+                    ledge_hang.state.released_at = Some(time.elapsed_seconds());
+                }
+            }
+        } else if basis_name == WalkMotionType::NAME {
+            let rising = tracker.velocity.linvel.dot(Vec3::from(tracker.up())) > 0.;
+            if !ctr.is_airborne() || rising || ledge_sensor.output.is_none() {
+                continue;
+            }
+
+            let Some(ledge_hang) = ctr.basis_mut::<LedgeHangBasis>(LedgeHangBasis::NAME) else {
+                continue;
+            };
+            let cooling_down = ledge_hang.state.released_at.is_some_and(|released_at| {
+                time.elapsed_seconds() - released_at < ledge_hang.input.regrab_cooldown
+            });
+            if cooling_down {
+                continue;
+            }
+
+            ledge_hang.state = LedgeHangState::default();
+            ctr.switch_basis(LedgeHangBasis::NAME);
+        }
+    }
+}
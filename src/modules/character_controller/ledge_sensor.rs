@@ -0,0 +1,100 @@
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use super::backend::{CharacterControllerBackend, RigidBodyTracker, SpatialQueryFilter};
+
+/// Looks for a grabbable ledge in front of the character's hands.
+#[derive(Debug, Component)]
+pub struct LedgeSensor {
+    /// Position of the hands relative to the character's origin, in the gravity frame.
+    pub hands_offset: Vec3,
+    /// How far in front of the hands a wall can be. The hands are on the character's center line,
+    /// so it has to reach past the character's own collider.
+    pub reach: f32,
+    /// How far above the hands the top of the ledge can be.
+    pub grab_height: f32,
+    pub filter: SpatialQueryFilter,
+    pub output: Option<LedgeSensorOutput>,
+}
+
+/// How far past the surface of the character's collider a ledge can be grabbed.
+const LEDGE_MARGIN: f32 = 0.4;
+
+impl Default for LedgeSensor {
+    /// Sized for a collider with a radius of 0.5.
+    fn default() -> Self {
+        Self {
+            hands_offset: Vec3::Y * 0.8,
+            reach: 0.5 + LEDGE_MARGIN,
+            grab_height: 0.6,
+            filter: SpatialQueryFilter::default(),
+            output: None,
+        }
+    }
+}
+
+impl LedgeSensor {
+    /// A sensor that reaches just past a collider of `radius` around the character's center.
+    pub fn for_body_radius(radius: f32) -> Self {
+        Self {
+            reach: radius + LEDGE_MARGIN,
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LedgeSensorOutput {
+    pub entity: Entity,
+    /// Point on top of the ledge, right behind its edge.
+    pub point: Vec3,
+    /// Normal of the wall below the ledge, perpendicular to the character's up.
+    pub wall_normal: Vec3,
+}
+
+pub fn ledge_sensor_system<B: CharacterControllerBackend>(
+    mut q: Query<(Entity, &mut LedgeSensor, &Transform, &RigidBodyTracker)>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
+) {
+    let spatial_query = B::spatial_query(&spatial_query);
+
+    for (entity, mut sensor, transform, tracker) in q.iter_mut() {
+        let filter = sensor.filter.excluding(entity);
+        let up = Vec3::from(tracker.up());
+        let Some(forward) = Vec3::from(transform.forward())
+            .reject_from(up)
+            .try_normalize()
+        else {
+            sensor.output = None;
+            continue;
+        };
+        let hands = transform.translation + tracker.gravity_frame() * sensor.hands_offset;
+
+        sensor.output = (|| {
+            let wall = spatial_query.cast_ray(hands, forward, sensor.reach, filter, None)?;
+            if wall.normal.dot(up).abs() > 0.3 {
+                return None;
+            }
+
+            // the wall has to end below the top of the grab range
+            let above_hands = hands + up * sensor.grab_height;
+            if spatial_query
+                .cast_ray(above_hands, forward, sensor.reach, filter, None)
+                .is_some()
+            {
+                return None;
+            }
+
+            let over_ledge = wall.point - wall.normal * 0.2 + up * sensor.grab_height;
+            let top = spatial_query.cast_ray(over_ledge, -up, sensor.grab_height, filter, None)?;
+            if top.normal.dot(up) < 0.7 {
+                return None;
+            }
+
+            Some(LedgeSensorOutput {
+                entity: top.entity,
+                point: top.point,
+                wall_normal: wall.normal.reject_from(up).normalize_or_zero(),
+            })
+        })();
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use super::backend::{CharacterControllerBackend, RigidBodyTracker};
+
+/// A box of liquid. Needs a sensor collider so the backend reports the characters inside it.
+#[derive(Debug, Component)]
+pub struct WaterVolume {
+    pub half_extents: Vec3,
+}
+
+impl WaterVolume {
+    /// How far below the surface `point` is, measured along `up`. The surface is the face of the
+    /// (possibly rotated) box that reaches furthest along `up`.
+    pub fn depth_of(&self, transform: &GlobalTransform, point: Vec3, up: Vec3) -> f32 {
+        let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+        let half_extents = self.half_extents * scale.abs();
+        let reach = (rotation * Vec3::X).dot(up).abs() * half_extents.x
+            + (rotation * Vec3::Y).dot(up).abs() * half_extents.y
+            + (rotation * Vec3::Z).dot(up).abs() * half_extents.z;
+        (translation - point).dot(up) + reach
+    }
+}
+
+/// Reports the liquid volume the character is inside of, if any.
+#[derive(Debug, Default, Component)]
+pub struct LiquidSensor {
+    pub output: Option<LiquidSensorOutput>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidSensorOutput {
+    pub entity: Entity,
+    /// How far below the surface the character's origin is, along the character's up.
+    pub depth: f32,
+}
+
+pub fn liquid_sensor_system<B: CharacterControllerBackend>(
+    mut q: Query<(Entity, &mut LiquidSensor, &Transform, &RigidBodyTracker)>,
+    volumes: Query<(&WaterVolume, &GlobalTransform)>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
+) {
+    let spatial_query = B::spatial_query(&spatial_query);
+
+    for (entity, mut sensor, transform, tracker) in q.iter_mut() {
+        let up = Vec3::from(tracker.up());
+        sensor.output = spatial_query
+            .intersections_with(entity)
+            .into_iter()
+            .filter_map(|other| {
+                let (volume, volume_transform) = volumes.get(other).ok()?;
+                Some(LiquidSensorOutput {
+                    entity: other,
+                    depth: volume.depth_of(volume_transform, transform.translation, up),
+                })
+            })
+            .max_by(|a, b| a.depth.total_cmp(&b.depth));
+    }
+}
@@ -1,17 +1,34 @@
+use std::marker::PhantomData;
+
 use bevy::{
-    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    ecs::{
+        schedule::{InternedScheduleLabel, ScheduleLabel},
+        system::StaticSystemParam,
+    },
     prelude::*,
+    time::Stopwatch,
+    transform::TransformSystem,
     utils::{Entry, HashMap},
 };
 
-use bevy_rapier3d::prelude::*;
-
 use crate::modules::character_controller::traits::action::ActionLifecycleDirective;
 
 use self::{
-    motion::{apply_motion_system, debug_motion_system, Motion},
+    actions::grapple_rope_system,
+    backend::{CharacterControllerBackend, RigidBodyTracker},
+    cooldown::debug_cooldown_system,
+    gravity::gravity_system,
+    inspector::tuning_inspector_system,
+    interpolation::{interpolate_visuals_system, record_transform_interpolation_system},
+    ledge_hang::ledge_grab_system,
+    ledge_sensor::ledge_sensor_system,
+    liquid_sensor::liquid_sensor_system,
+    motion::{Motion, VelChange},
     player_input::player_keyboard_input_system,
-    proximity_sensor::{cast_ray_system, ProximitySensor},
+    proximity_sensor::proximity_sensor_system,
+    separation::character_separation_system,
+    step_sensor::step_sensor_system,
+    swim::liquid_switch_system,
     traits::{
         action::{
             Action, ActionContext, ActionInitiationDirective, ActionLifecycle, BoxableActionType,
@@ -19,17 +36,54 @@ use self::{
         },
         basis::{Basis, BasisContext, BoxableBasis, DynamicBasis},
     },
+    tuning::{sync_movement_tuning_system, MovementTuningLoader},
+    wall_sensor::wall_sensor_system,
 };
 
 pub mod actions;
+pub mod backend;
+mod cooldown;
+mod events;
+mod gravity;
+mod inspector;
+mod interpolation;
+mod landing;
+mod ledge_hang;
+mod ledge_sensor;
+mod liquid_sensor;
 mod motion;
 mod player_input;
 mod proximity_sensor;
+mod separation;
+mod step_sensor;
+mod swim;
 mod traits;
+mod tuning;
 mod utils;
 mod walk;
+mod wall_sensor;
 
-pub use walk::WalkMotionType;
+#[cfg(test)]
+mod tests;
+
+pub use cooldown::{ActionCooldown, ActionCooldownConfig};
+pub use events::{
+    ActionFinishedEvent, ActionInterruptedEvent, ActionRejectedEvent, ActionRejectionReason,
+    ActionStartedEvent, BasisChangedEvent, LandedEvent,
+};
+pub use gravity::{GravitySource, GravityZone};
+pub use interpolation::{InterpolatedVisual, TransformInterpolation};
+pub use landing::LandingRecovery;
+pub use ledge_hang::{LedgeHangBasis, LedgeHangPhase, LedgeHangState};
+pub use ledge_sensor::{LedgeSensor, LedgeSensorOutput};
+pub use liquid_sensor::{LiquidSensor, LiquidSensorOutput, WaterVolume};
+pub use proximity_sensor::{ProximitySensor, ProximitySensorCast, ProximitySensorOutput};
+pub use separation::CharacterSeparation;
+pub use step_sensor::{StepSensor, StepSensorOutput};
+pub use swim::{SwimBasis, SwimState};
+pub use tuning::{DashTuning, JumpTuning, MovementTuning, MovementTuningHandle, WalkTuning};
+pub use walk::{FacingMode, SpringConfig, WalkMotionType};
+pub use wall_sensor::{WallSensor, WallSensorOutput};
 
 /// The user controls should be applied in this system set.
 #[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
@@ -51,9 +105,29 @@ pub enum CharacterControllerPipelineStages {
 #[derive(Default, Bundle)]
 pub struct CharacterControllerBundle {
     controller: CharacterController,
-    physics: CharacterControllerPhysicsBundle,
+    tracker: RigidBodyTracker,
     motion: Motion,
     proximity_sensor: ProximitySensor,
+    wall_sensor: WallSensor,
+    ledge_sensor: LedgeSensor,
+    step_sensor: StepSensor,
+    liquid_sensor: LiquidSensor,
+    separation: CharacterSeparation,
+}
+
+impl CharacterControllerBundle {
+    pub fn with_proximity_sensor(mut self, proximity_sensor: ProximitySensor) -> Self {
+        self.proximity_sensor = proximity_sensor;
+        self
+    }
+
+    /// Sizes the sensors that look out from the character's center for a collider of `radius`.
+    pub fn with_body_radius(mut self, radius: f32) -> Self {
+        self.wall_sensor = WallSensor::for_body_radius(radius);
+        self.ledge_sensor = LedgeSensor::for_body_radius(radius);
+        self.step_sensor = StepSensor::for_body_radius(radius);
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -61,12 +135,33 @@ struct FedEntry {
     fed_this_frame: bool,
 }
 
+struct ContenderAction {
+    name: &'static str,
+    action: Box<dyn DynamicActionType>,
+    being_fed_for: Stopwatch,
+    delayed: bool,
+}
+
 #[derive(Default, Component)]
 pub struct CharacterController {
     current_basis: Option<(&'static str, Box<dyn DynamicBasis>)>,
+    /// Bases that were fed but are not the current one, kept until the controller switches to them.
+    stashed_bases: HashMap<&'static str, Box<dyn DynamicBasis>>,
     current_action: Option<(&'static str, Box<dyn DynamicActionType>)>,
-    contender_action: Option<(&'static str, Box<dyn DynamicActionType>)>,
+    contender_action: Option<ContenderAction>,
     actions_being_fed: HashMap<&'static str, FedEntry>,
+    cooldowns: HashMap<&'static str, ActionCooldown>,
+    /// Why each action fed but not started was last rejected, to report each rejection once.
+    rejections: HashMap<&'static str, ActionRejectionReason>,
+    /// Basis that was current when the controller last ran, to notice switches.
+    last_basis_name: Option<&'static str>,
+    ground: Option<ProximitySensorOutput>,
+    /// Seconds since the character last left the ground, zero while grounded.
+    airtime: f32,
+    /// Times each action started since the character last left the ground.
+    air_uses: HashMap<&'static str, u32>,
+    /// Lockout left after a hard landing, see [`LandingRecovery`].
+    recovery: Option<Timer>,
 }
 
 impl CharacterController {
@@ -74,113 +169,382 @@ impl CharacterController {
         self.named_motion_type(M::NAME, m);
     }
 
+    /// Feeds the basis registered under `name`.
+    ///
+    /// The first basis fed becomes the current one. Bases fed under other names are stashed,
+    /// keeping their state, until [`CharacterController::switch_basis`] makes them current.
     pub fn named_motion_type<M: Basis>(&mut self, name: &'static str, motion_type: M) -> &mut Self {
-        if let Some((existing_name, existing_motion_type)) =
-            self.current_basis.as_mut().and_then(|(n, m)| {
-                let m = m.as_mut_any().downcast_mut::<BoxableBasis<M>>()?;
-                Some((n, m))
-            })
-        {
-            *existing_name = name;
-            existing_motion_type.input = motion_type;
-        } else {
-            self.current_basis = Some((name, Box::new(BoxableBasis::new(motion_type))))
+        let Some((current_name, _)) = &self.current_basis else {
+            self.current_basis = Some((name, Box::new(BoxableBasis::new(motion_type))));
+            return self;
+        };
+
+        if *current_name != name && !self.stashed_bases.contains_key(name) {
+            self.stashed_bases
+                .insert(name, Box::new(BoxableBasis::new(motion_type)));
+            return self;
         }
+
+        self.basis_mut::<M>(name)
+            .expect("[Basis] Multiple basis types registered with same name {name:?}")
+            .input = motion_type;
         self
     }
 
+    /// Makes the stashed basis `name` the current one, stashing the previous current basis.
+    /// Returns false if no basis was fed under that name.
+    pub fn switch_basis(&mut self, name: &'static str) -> bool {
+        if self.basis_name() == Some(name) {
+            return true;
+        }
+        let Some(basis) = self.stashed_bases.remove(name) else {
+            return false;
+        };
+
+        if let Some((previous_name, previous_basis)) = self.current_basis.replace((name, basis)) {
+            self.stashed_bases.insert(previous_name, previous_basis);
+        }
+        true
+    }
+
+    pub fn basis_name(&self) -> Option<&'static str> {
+        self.current_basis.as_ref().map(|(name, _)| *name)
+    }
+
+    /// The current basis and its state, if it is a `B`.
+    pub fn basis<B: Basis>(&self) -> Option<(&B, &B::State)> {
+        let (_, basis) = self.current_basis.as_ref()?;
+        let basis = basis.as_any().downcast_ref::<BoxableBasis<B>>()?;
+        Some((&basis.input, &basis.state))
+    }
+
+    pub fn action_name(&self) -> Option<&'static str> {
+        self.current_action.as_ref().map(|(name, _)| *name)
+    }
+
+    /// The current action and its state, if it is an `A`.
+    pub fn action<A: Action>(&self) -> Option<(&A, &A::State)> {
+        let (_, action) = self.current_action.as_ref()?;
+        let action = action.as_any().downcast_ref::<BoxableActionType<A>>()?;
+        Some((&action.input, &action.state))
+    }
+
+    /// Name of the action waiting to start, if any.
+    pub fn contender_name(&self) -> Option<&'static str> {
+        self.contender_action
+            .as_ref()
+            .map(|contender| contender.name)
+    }
+
+    /// Whether the action `name` is currently being fed.
+    pub fn is_fed(&self, name: &str) -> bool {
+        self.actions_being_fed.contains_key(name)
+    }
+
+    /// Whether the current basis considers the character airborne. False until a basis is fed.
+    pub fn is_airborne(&self) -> bool {
+        self.current_basis
+            .as_ref()
+            .is_some_and(|(_, basis)| basis.is_airborne())
+    }
+
+    /// What the character stood on when the controller last ran, or `None` while airborne.
+    pub fn ground(&self) -> Option<ProximitySensorOutput> {
+        self.ground
+    }
+
+    /// Charges of the action registered under `name`, once it has been fed with a cooldown.
+    pub fn cooldown(&self, name: &str) -> Option<&ActionCooldown> {
+        self.cooldowns.get(name)
+    }
+
+    /// Seconds the character has been airborne, zero while grounded.
+    pub fn airtime(&self) -> f32 {
+        self.airtime
+    }
+
+    /// Times the action registered under `name` started since the character left the ground.
+    /// Resets on landing.
+    pub fn air_uses(&self, name: &str) -> u32 {
+        self.air_uses.get(name).copied().unwrap_or(0)
+    }
+
+    /// Whether the character is locked out after a hard landing.
+    pub fn is_recovering(&self) -> bool {
+        self.recovery.is_some()
+    }
+
+    pub fn cooldowns(&self) -> impl Iterator<Item = (&'static str, &ActionCooldown)> {
+        self.cooldowns
+            .iter()
+            .map(|(name, cooldown)| (*name, cooldown))
+    }
+
+    fn basis_mut<M: Basis>(&mut self, name: &'static str) -> Option<&mut BoxableBasis<M>> {
+        let basis = match &mut self.current_basis {
+            Some((current_name, basis)) if *current_name == name => basis,
+            _ => self.stashed_bases.get_mut(name)?,
+        };
+        basis.as_mut_any().downcast_mut::<BoxableBasis<M>>()
+    }
+
     pub fn action_type<A: Action>(&mut self, a: A) {
         self.named_action(A::NAME, a);
     }
 
     pub fn named_action<A: Action>(&mut self, name: &'static str, a: A) {
-        match self.actions_being_fed.entry(name) {
+        let newly_fed = match self.actions_being_fed.entry(name) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().fed_this_frame = true;
-                match self.current_action.as_mut() {
-                    None => {
-                        self.contender_action = Some((name, Box::new(BoxableActionType::new(a))));
-                    }
-                    Some((current_action_name, current_action)) => {
-                        if *current_action_name == name {
-                            let current_action = current_action
-                                .as_mut_any()
-                                .downcast_mut::<BoxableActionType<A>>()
-                                .expect("[Occupied] Multiple action types registered with same name {name:?}");
-
-                            current_action.input = a;
-                        }
-                    }
-                }
+                false
             }
             Entry::Vacant(entry) => {
                 entry.insert(FedEntry {
                     fed_this_frame: true,
                 });
+                true
+            }
+        };
 
-                match self.contender_action.as_mut() {
-                    Some((_, contender_action)) => {
-                        let contender_action = contender_action
-                            .as_mut_any()
-                            .downcast_mut::<BoxableActionType<A>>()
-                            .expect(
-                                "[Vacant] Multiple action types registered with same name {name:?}",
-                            );
+        if newly_fed {
+            self.feed_contender(name, a).being_fed_for.reset();
+            return;
+        }
 
-                        contender_action.input = a;
-                    }
-                    None => {
-                        self.contender_action = Some((name, Box::new(BoxableActionType::new(a))));
-                    }
+        match self.current_action.as_mut() {
+            None => {
+                self.feed_contender(name, a);
+            }
+            Some((current_action_name, current_action)) => {
+                if *current_action_name == name {
+                    let current_action = current_action
+                        .as_mut_any()
+                        .downcast_mut::<BoxableActionType<A>>()
+                        .expect(
+                            "[Occupied] Multiple action types registered with same name {name:?}",
+                        );
+
+                    current_action.input = a;
                 }
             }
-        };
+        }
+    }
+
+    fn feed_contender<A: Action>(&mut self, name: &'static str, a: A) -> &mut ContenderAction {
+        let same_contender = self
+            .contender_action
+            .as_ref()
+            .is_some_and(|contender| contender.name == name);
+
+        if !same_contender {
+            self.contender_action = Some(ContenderAction {
+                name,
+                action: Box::new(BoxableActionType::new(a)),
+                being_fed_for: Stopwatch::new(),
+                delayed: false,
+            });
+            return self
+                .contender_action
+                .as_mut()
+                .expect("contender_action was just set");
+        }
+
+        let contender = self.contender_action.as_mut().expect("checked above");
+        contender
+            .action
+            .as_mut_any()
+            .downcast_mut::<BoxableActionType<A>>()
+            .expect("[Contender] Multiple action types registered with same name {name:?}")
+            .input = a;
+        contender
     }
 }
 
-pub fn controller_system(
+pub fn controller_system<B: CharacterControllerBackend>(
     time: Res<Time>,
-    rapier_config: Res<RapierConfiguration>,
-    rapier_context: Res<RapierContext>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
+    mut ev_action_started: EventWriter<ActionStartedEvent>,
+    mut ev_action_finished: EventWriter<ActionFinishedEvent>,
+    mut ev_action_interrupted: EventWriter<ActionInterruptedEvent>,
+    mut ev_action_rejected: EventWriter<ActionRejectedEvent>,
+    mut ev_basis_changed: EventWriter<BasisChangedEvent>,
+    mut ev_landed: EventWriter<LandedEvent>,
     mut query: Query<(
+        Entity,
         &Transform,
-        &Velocity,
+        &RigidBodyTracker,
         &mut CharacterController,
         &ProximitySensor,
+        &WallSensor,
+        &LedgeSensor,
+        &StepSensor,
+        &LiquidSensor,
+        Option<&LandingRecovery>,
         &mut motion::Motion,
     )>,
 ) {
-    for (transform, velocity, mut ctr, sensor, mut motion) in query.iter_mut() {
+    let spatial_query = B::spatial_query(&spatial_query);
+
+    for (
+        entity,
+        transform,
+        tracker,
+        mut ctr,
+        sensor,
+        wall_sensor,
+        ledge_sensor,
+        step_sensor,
+        liquid_sensor,
+        landing_recovery,
+        mut motion,
+    ) in query.iter_mut()
+    {
         let ctr = ctr.as_mut();
         let motion = motion.as_mut();
 
+        for cooldown in ctr.cooldowns.values_mut() {
+            cooldown.tick(time.delta());
+        }
+        if ctr
+            .recovery
+            .as_mut()
+            .is_some_and(|timer| timer.tick(time.delta()).finished())
+        {
+            ctr.recovery = None;
+        }
+
+        let basis_name = ctr.basis_name();
+        if basis_name != ctr.last_basis_name {
+            if let Some(to) = basis_name {
+                ev_basis_changed.send(BasisChangedEvent {
+                    entity,
+                    from: ctr.last_basis_name,
+                    to,
+                });
+            }
+            ctr.last_basis_name = basis_name;
+        }
+
+        ctr.ground = None;
         if let Some((_, motion_type)) = &mut ctr.current_basis {
             let motion_type = motion_type.as_mut();
+            // read before `apply`, which resets it on the frame the character lands
+            let ungrounded_time = motion_type.ungrounded_time();
             motion_type.apply(
                 BasisContext {
                     frame_duration: time.delta_seconds(),
                     proximity_sensor_output: sensor.output,
+                    ledge_sensor_output: ledge_sensor.output,
+                    step_sensor_output: step_sensor.output,
+                    liquid_sensor_output: liquid_sensor.output,
                     transform: *transform,
-                    velocity: *velocity,
-                    gravity: rapier_config.gravity,
+                    velocity: tracker.velocity,
+                    gravity: tracker.gravity,
+                    up: tracker.up(),
                 },
                 motion,
             );
+            ctr.ground = sensor.output.filter(|_| !motion_type.is_airborne());
+            // a drop shorter than the coyote time lands without ever counting as airborne
+            let landed_within_coyote_time =
+                ungrounded_time > 0. && motion_type.ungrounded_time() == 0.;
+            if motion_type.is_airborne() {
+                if ctr.airtime == 0. {
+                    // left the ground at the start of the coyote time, not when it ran out
+                    ctr.airtime = ungrounded_time;
+                }
+                ctr.airtime += time.delta_seconds();
+            } else if ctr.airtime > 0. || landed_within_coyote_time {
+                // only landings on the ground, not e.g. switching to swimming
+                if ctr.ground.is_some() {
+                    let impact_speed = -tracker.velocity.linvel.dot(Vec3::from(tracker.up()));
+                    ev_landed.send(LandedEvent {
+                        entity,
+                        airtime: ctr.airtime.max(ungrounded_time),
+                        impact_velocity: tracker.velocity.linvel,
+                        impact_speed,
+                    });
+                    if let Some(recovery) = landing_recovery
+                        .filter(|recovery| impact_speed >= recovery.min_impact_speed)
+                    {
+                        ctr.recovery =
+                            Some(Timer::from_seconds(recovery.duration, TimerMode::Once));
+                    }
+                }
+                ctr.airtime = 0.;
+                ctr.air_uses.clear();
+            }
 
-            let has_valid_contender = if let Some((_, contender_action)) = &ctr.contender_action {
-                let initiation_decision = contender_action.initiation_decision(ActionContext {
-                    frame_duration: time.delta_seconds(),
-                    gravity: rapier_config.gravity,
-                    proximity_sensor_output: sensor.output,
-                    transform: *transform,
-                    velocity: *velocity,
-                    motion_type,
-                    rapier_context: rapier_context.as_ref(),
-                });
+            let recovering = ctr.recovery.is_some();
+            if recovering {
+                // keep the basis holding the character up, but stop it on the ground
+                let up = Vec3::from(tracker.up());
+                let ground_velocity = sensor
+                    .output
+                    .map_or(Vec3::ZERO, |ground| ground.ground_velocity.linvel);
+                motion.linvel = VelChange {
+                    accel: motion.linvel.accel.project_onto(up),
+                    boost: motion.linvel.boost.project_onto(up)
+                        + (ground_velocity - tracker.velocity.linvel).reject_from(up),
+                    impulse: motion.linvel.impulse,
+                };
+            }
+
+            let has_valid_contender = if let Some(contender) = &mut ctr.contender_action {
+                contender.being_fed_for.tick(time.delta());
+                if let Some(config) = contender.action.cooldown() {
+                    ctr.cooldowns
+                        .entry(contender.name)
+                        .or_insert_with(|| ActionCooldown::new(config))
+                        .configure(config);
+                }
+                let cooldown_ready = ctr
+                    .cooldowns
+                    .get(contender.name)
+                    .map_or(true, ActionCooldown::is_ready);
+                let initiation_decision = if recovering {
+                    ActionInitiationDirective::Delay
+                } else if !cooldown_ready {
+                    ActionInitiationDirective::Reject
+                } else {
+                    contender.action.initiation_decision(
+                        ActionContext {
+                            frame_duration: time.delta_seconds(),
+                            gravity: tracker.gravity,
+                            up: tracker.up(),
+                            proximity_sensor_output: sensor.output,
+                            wall_sensor_output: wall_sensor.output,
+                            ledge_sensor_output: ledge_sensor.output,
+                            step_sensor_output: step_sensor.output,
+                            liquid_sensor_output: liquid_sensor.output,
+                            transform: *transform,
+                            velocity: tracker.velocity,
+                            motion_type,
+                            spatial_query,
+                            cooldown: ctr.cooldowns.get(contender.name),
+                            air_uses: ctr.air_uses.get(contender.name).copied().unwrap_or(0),
+                        },
+                        &contender.being_fed_for,
+                    )
+                };
 
+                contender.delayed = initiation_decision == ActionInitiationDirective::Delay;
                 match initiation_decision {
                     ActionInitiationDirective::Allow => true,
+                    ActionInitiationDirective::Delay => false,
                     ActionInitiationDirective::Reject => {
+                        let name = contender.name;
+                        let reason = match cooldown_ready {
+                            true => ActionRejectionReason::Declined,
+                            false => ActionRejectionReason::Cooldown,
+                        };
+                        if ctr.rejections.insert(name, reason) != Some(reason) {
+                            ev_action_rejected.send(ActionRejectedEvent {
+                                entity,
+                                name,
+                                reason,
+                            });
+                        }
                         ctr.contender_action = None;
                         false
                     }
@@ -189,7 +553,50 @@ pub fn controller_system(
                 false
             };
 
-            match &mut ctr.current_action {
+            let preempt_current = has_valid_contender
+                && match (&ctr.current_action, &ctr.contender_action) {
+                    (Some((_, current_action)), Some(contender)) => {
+                        contender.action.priority() > current_action.priority()
+                            || current_action.can_be_cancelled()
+                    }
+                    _ => false,
+                };
+            if preempt_current {
+                let (interrupted_name, mut interrupted_action) = ctr
+                    .current_action
+                    .take()
+                    .expect("preempt_current can only be true if current_action is Some");
+                ev_action_interrupted.send(ActionInterruptedEvent {
+                    entity,
+                    name: interrupted_name,
+                    interrupted_by: ctr
+                        .contender_action
+                        .as_ref()
+                        .map(|contender| contender.name)
+                        .expect("preempt_current can only be true if contender_action is Some"),
+                });
+                interrupted_action.interrupt(
+                    ActionContext {
+                        frame_duration: time.delta_seconds(),
+                        gravity: tracker.gravity,
+                        up: tracker.up(),
+                        proximity_sensor_output: sensor.output,
+                        wall_sensor_output: wall_sensor.output,
+                        ledge_sensor_output: ledge_sensor.output,
+                        step_sensor_output: step_sensor.output,
+                        liquid_sensor_output: liquid_sensor.output,
+                        transform: *transform,
+                        velocity: tracker.velocity,
+                        motion_type,
+                        spatial_query,
+                        cooldown: ctr.cooldowns.get(interrupted_name),
+                        air_uses: ctr.air_uses.get(interrupted_name).copied().unwrap_or(0),
+                    },
+                    motion,
+                );
+            }
+
+            let start_contender = match &mut ctr.current_action {
                 Some((action_name, action_type)) => {
                     let lifecycle = if ctr
                         .actions_being_fed
@@ -205,69 +612,83 @@ pub fn controller_system(
                     let directive = action_type.apply(
                         ActionContext {
                             frame_duration: time.delta_seconds(),
-                            gravity: rapier_config.gravity,
+                            gravity: tracker.gravity,
+                            up: tracker.up(),
                             proximity_sensor_output: sensor.output,
+                            wall_sensor_output: wall_sensor.output,
+                            ledge_sensor_output: ledge_sensor.output,
+                            step_sensor_output: step_sensor.output,
+                            liquid_sensor_output: liquid_sensor.output,
                             transform: *transform,
-                            velocity: *velocity,
+                            velocity: tracker.velocity,
                             motion_type,
-                            rapier_context: rapier_context.as_ref(),
+                            spatial_query,
+                            cooldown: ctr.cooldowns.get(action_name),
+                            air_uses: ctr.air_uses.get(action_name).copied().unwrap_or(0),
                         },
                         lifecycle,
                         motion,
                     );
 
                     match directive {
-                        ActionLifecycleDirective::Active => {}
+                        ActionLifecycleDirective::Active => false,
                         ActionLifecycleDirective::Finished => {
-                            ctr.current_action = if has_valid_contender {
-                                let (contender_name, mut contender_action) =
-                                    ctr.contender_action.take().expect(
-                                        "has_valid_contender can only be true if contender_action is Some",
-                                    );
-                                contender_action.apply(
-                                    ActionContext {
-                                        frame_duration: time.delta_seconds(),
-                                        gravity: rapier_config.gravity,
-                                        proximity_sensor_output: sensor.output,
-                                        transform: *transform,
-                                        velocity: *velocity,
-                                        motion_type,
-                                        rapier_context: rapier_context.as_ref(),
-                                    },
-                                    ActionLifecycle::Started,
-                                    motion,
-                                );
-                                // ctr.contender_action = None;
-                                Some((contender_name, contender_action))
-                            } else {
-                                None
-                            }
+                            ev_action_finished.send(ActionFinishedEvent {
+                                entity,
+                                name: *action_name,
+                            });
+                            ctr.current_action = None;
+                            has_valid_contender
                         }
                     }
                 }
-                None => {
-                    if has_valid_contender {
-                        let (contender_name, mut contender_action) =
-                            ctr.contender_action.take().expect(
-                                "has_valid_contender can only be true if contender_action is Some",
-                            );
-                        contender_action.apply(
-                            ActionContext {
-                                frame_duration: time.delta_seconds(),
-                                gravity: rapier_config.gravity,
-                                proximity_sensor_output: sensor.output,
-                                transform: *transform,
-                                velocity: *velocity,
-                                motion_type,
-                                rapier_context: rapier_context.as_ref(),
-                            },
-                            ActionLifecycle::Started,
-                            motion,
-                        );
-                        ctr.current_action = Some((contender_name, contender_action));
-                        ctr.contender_action = None;
-                    }
+                None => has_valid_contender,
+            };
+
+            if start_contender {
+                let ContenderAction {
+                    name: contender_name,
+                    action: mut contender_action,
+                    ..
+                } = ctr
+                    .contender_action
+                    .take()
+                    .expect("has_valid_contender can only be true if contender_action is Some");
+                contender_action.apply(
+                    ActionContext {
+                        frame_duration: time.delta_seconds(),
+                        gravity: tracker.gravity,
+                        up: tracker.up(),
+                        proximity_sensor_output: sensor.output,
+                        wall_sensor_output: wall_sensor.output,
+                        ledge_sensor_output: ledge_sensor.output,
+                        step_sensor_output: step_sensor.output,
+                        liquid_sensor_output: liquid_sensor.output,
+                        transform: *transform,
+                        velocity: tracker.velocity,
+                        motion_type,
+                        spatial_query,
+                        cooldown: ctr.cooldowns.get(contender_name),
+                        air_uses: ctr.air_uses.get(contender_name).copied().unwrap_or(0),
+                    },
+                    ActionLifecycle::Started,
+                    motion,
+                );
+                if let Some(cooldown) = ctr.cooldowns.get_mut(contender_name) {
+                    cooldown.spend();
                 }
+                ctr.rejections.remove(contender_name);
+                if motion_type.is_airborne() {
+                    *ctr.air_uses.entry(contender_name).or_default() += 1;
+                }
+                if contender_action.violates_coyote_time() {
+                    motion_type.violate_coyote_time();
+                }
+                ev_action_started.send(ActionStartedEvent {
+                    entity,
+                    name: contender_name,
+                });
+                ctr.current_action = Some((contender_name, contender_action));
             }
         }
 
@@ -281,42 +702,55 @@ pub fn controller_system(
             }
         });
 
-        if let Some((contender_name, ..)) = ctr.contender_action {
-            if !ctr.actions_being_fed.contains_key(contender_name) {
+        ctr.rejections
+            .retain(|name, _| ctr.actions_being_fed.contains_key(name));
+
+        // A delayed contender stays buffered after its input is released
+        if let Some(contender) = &ctr.contender_action {
+            if !contender.delayed && !ctr.actions_being_fed.contains_key(contender.name) {
                 ctr.contender_action = None;
             }
         }
     }
 }
 
-#[derive(Default, Bundle)]
-pub struct CharacterControllerPhysicsBundle {
-    velocity: Velocity,
-    external_force: ExternalForce,
-    impulse: ExternalImpulse,
-    read_mass_properties: ReadMassProperties,
-}
-
-pub struct CharacterControllerPlugin {
+/// Runs the character controller on the physics backend `B`, e.g.
+/// `CharacterControllerPlugin::<RapierBackend>::default()`.
+///
+/// To run on a fixed timestep pass `FixedUpdate` to [`CharacterControllerPlugin::new`], step the
+/// physics in the same schedule and add [`TransformInterpolation`] to the characters so their
+/// [`InterpolatedVisual`] children render smoothly.
+pub struct CharacterControllerPlugin<B: CharacterControllerBackend> {
     schedule: InternedScheduleLabel,
+    _phantom: PhantomData<B>,
 }
 
-impl CharacterControllerPlugin {
+impl<B: CharacterControllerBackend> CharacterControllerPlugin<B> {
     pub fn new(schedule: impl ScheduleLabel) -> Self {
         Self {
             schedule: schedule.intern(),
+            _phantom: PhantomData,
         }
     }
 }
 
-impl Default for CharacterControllerPlugin {
+impl<B: CharacterControllerBackend> Default for CharacterControllerPlugin<B> {
     fn default() -> Self {
         Self::new(Update)
     }
 }
 
-impl Plugin for CharacterControllerPlugin {
+impl<B: CharacterControllerBackend> Plugin for CharacterControllerPlugin<B> {
     fn build(&self, app: &mut App) {
+        app.add_event::<ActionStartedEvent>()
+            .add_event::<ActionFinishedEvent>()
+            .add_event::<ActionInterruptedEvent>()
+            .add_event::<ActionRejectedEvent>()
+            .add_event::<BasisChangedEvent>()
+            .add_event::<LandedEvent>()
+            .init_asset::<MovementTuning>()
+            .init_asset_loader::<MovementTuningLoader>();
+
         app.configure_sets(
             self.schedule,
             (
@@ -331,7 +765,18 @@ impl Plugin for CharacterControllerPlugin {
 
         app.add_systems(
             self.schedule,
-            cast_ray_system.in_set(CharacterControllerPipelineStages::Sensors),
+            (
+                gravity_system::<B>,
+                (
+                    proximity_sensor_system::<B>,
+                    wall_sensor_system::<B>,
+                    ledge_sensor_system::<B>,
+                    step_sensor_system::<B>,
+                    liquid_sensor_system::<B>,
+                ),
+            )
+                .chain()
+                .in_set(CharacterControllerPipelineStages::Sensors),
         );
 
         app.add_systems(
@@ -341,13 +786,33 @@ impl Plugin for CharacterControllerPlugin {
 
         app.add_systems(
             self.schedule,
-            controller_system.in_set(CharacterControllerPipelineStages::Logic),
+            (
+                ledge_grab_system,
+                liquid_switch_system,
+                controller_system::<B>,
+                character_separation_system,
+            )
+                .chain()
+                .in_set(CharacterControllerPipelineStages::Logic),
         );
 
+        // egui wants exactly one pass per rendered frame, whatever schedule the controller uses
         app.add_systems(
-            self.schedule,
-            (apply_motion_system, debug_motion_system)
-                .in_set(CharacterControllerPipelineStages::Motors),
+            Update,
+            (
+                sync_movement_tuning_system,
+                tuning_inspector_system,
+                debug_cooldown_system,
+                grapple_rope_system,
+            ),
         );
+
+        app.add_systems(FixedPostUpdate, record_transform_interpolation_system);
+        app.add_systems(
+            PostUpdate,
+            interpolate_visuals_system.before(TransformSystem::TransformPropagate),
+        );
+
+        B::build(app, self.schedule);
     }
 }
@@ -1,18 +1,58 @@
 use bevy::prelude::*;
 
-use crate::world3d::{Player, PlayerCamera};
+use crate::world3d::{Player, PlayerCamera, PlayerTarget};
 
 use super::{
-    actions::{AttackAction, DashAction, JumpAction},
-    CharacterController, WalkMotionType,
+    actions::{
+        AttackAction, DoubleJumpAction, GlideAction, GrappleAction, JumpAction, MotionProfile,
+        ScriptedMotionAction, WallJumpAction, WallSlideAction,
+    },
+    backend::RigidBodyTracker,
+    traits::{action::Action, basis::Basis},
+    CharacterController, FacingMode, LedgeHangBasis, MovementTuning, ProximitySensor, SwimBasis,
+    WalkMotionType, WallSensor,
 };
 
+/// Walk basis facing for the player. Strafes around a locked target, otherwise C toggles between
+/// facing the movement direction and the camera's forward.
+fn facing_mode(
+    target: Option<&GlobalTransform>,
+    camera: Option<&Transform>,
+    camera_facing: bool,
+) -> FacingMode {
+    if let Some(target) = target {
+        return FacingMode::LookAt(target.translation());
+    }
+    match camera {
+        Some(camera) if camera_facing => FacingMode::Direction(camera.forward()),
+        _ => FacingMode::Movement,
+    }
+}
+
 pub fn player_keyboard_input_system(
     keyboard: Res<ButtonInput<KeyCode>>,
-    mut ctr_query: Query<&mut CharacterController, With<Player>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut camera_facing: Local<bool>,
+    mut camera_facing_toggle_held: Local<bool>,
+    mut jump_held: Local<bool>,
+    mut lunge_held: Local<bool>,
+    mut ctr_query: Query<
+        (
+            &mut CharacterController,
+            &ProximitySensor,
+            &WallSensor,
+            &RigidBodyTracker,
+            Option<&MovementTuning>,
+        ),
+        With<Player>,
+    >,
     camera_query: Query<&Transform, With<PlayerCamera>>,
+    target_query: Query<&GlobalTransform, With<PlayerTarget>>,
 ) {
-    if let Ok(mut ctr) = ctr_query.get_single_mut() {
+    if let Ok((mut ctr, proximity_sensor, wall_sensor, tracker, tuning)) =
+        ctr_query.get_single_mut()
+    {
+        let tuning = tuning.copied().unwrap_or_default();
         let mut velocity = Vec3::ZERO;
 
         if keyboard.pressed(KeyCode::KeyW) {
@@ -30,7 +70,9 @@ pub fn player_keyboard_input_system(
 
         let (facing, velocity) = match camera_query.get_single() {
             Ok(transform) => {
-                velocity = transform.local_z() * velocity.z + transform.local_x() * velocity.x;
+                // steer along the ground of the character's own gravity, not the camera's
+                velocity = (transform.local_z() * velocity.z + transform.local_x() * velocity.x)
+                    .reject_from(Vec3::from(tracker.up()));
                 match velocity != Vec3::ZERO {
                     true => (
                         Some(Direction3d::new_unchecked(velocity.normalize_or_zero())),
@@ -42,24 +84,102 @@ pub fn player_keyboard_input_system(
             Err(_) => (None, velocity),
         };
 
+        // runs in the fixed schedule, where just_pressed can be missed or seen twice
+        let toggle_pressed = keyboard.pressed(KeyCode::KeyC);
+        if toggle_pressed && !*camera_facing_toggle_held {
+            *camera_facing = !*camera_facing;
+        }
+        *camera_facing_toggle_held = toggle_pressed;
+
         ctr.motion_type(WalkMotionType {
-            velocity: velocity.normalize_or_zero() * 15.,
-            facing,
+            facing_mode: facing_mode(
+                target_query.get_single().ok(),
+                camera_query.get_single().ok(),
+                *camera_facing,
+            ),
+            ..tuning.walk(velocity, facing)
+        });
+        ctr.motion_type(LedgeHangBasis {
+            climb: keyboard.pressed(KeyCode::KeyW),
+            drop: keyboard.pressed(KeyCode::KeyS),
+            // clear of the wall with the hero's 1.0 radius capsule
+            hang_distance: 1.1,
+            ..default()
+        });
+        ctr.motion_type(SwimBasis {
+            velocity: velocity.normalize_or_zero() * 8.,
+            facing: facing.and_then(|facing| Direction3d::new(facing.reject_from(Vec3::Y)).ok()),
             ..default()
         });
+        let hanging = ctr.basis_name() == Some(LedgeHangBasis::NAME);
+        let swimming = ctr.basis_name() == Some(SwimBasis::NAME);
+
+        // nothing below within sensor range, so a jump would only be buffered
+        let far_from_ground = proximity_sensor.output.is_none();
+        let pressing_into_wall = wall_sensor
+            .output
+            .is_some_and(|wall| far_from_ground && velocity.dot(wall.normal) < 0.);
+
+        let jump_pressed = keyboard.pressed(KeyCode::Space);
+        let jump_just_pressed = jump_pressed && !*jump_held;
+        *jump_held = jump_pressed;
 
-        if keyboard.pressed(KeyCode::Space) {
-            ctr.action_type(JumpAction { velocity: Vec3::Y });
+        // a fresh press in mid-air double jumps, holding on keeps feeding whichever jump is going
+        let double_jump = DoubleJumpAction::default();
+        let can_double_jump = !hanging
+            && !swimming
+            && ctr.is_airborne()
+            && ctr.air_uses(DoubleJumpAction::NAME) < double_jump.air_jumps;
+        // still waiting to start counts too, or the next frame's plain jump would replace it
+        let double_jumping = ctr.action_name() == Some(DoubleJumpAction::NAME)
+            || ctr.contender_name() == Some(DoubleJumpAction::NAME);
+
+        if jump_pressed {
+            if !hanging && !swimming && far_from_ground && wall_sensor.output.is_some() {
+                ctr.action_type(WallJumpAction::default());
+            } else if double_jumping || (jump_just_pressed && can_double_jump) {
+                // the first jump stays fed until the double jump takes over, so it isn't cut short
+                if ctr.action_name() == Some(JumpAction::NAME) {
+                    ctr.action_type(tuning.jump());
+                }
+                ctr.action_type(double_jump);
+            } else {
+                ctr.action_type(tuning.jump());
+            }
+        } else if pressing_into_wall && !hanging && !swimming {
+            ctr.action_type(WallSlideAction::default());
+        }
+
+        if keyboard.pressed(KeyCode::KeyE) && !hanging && !swimming {
+            ctr.action_type(GlideAction::default());
         }
 
         if let Some(facing) = facing {
             if keyboard.pressed(KeyCode::ShiftLeft) {
-                ctr.action_type(DashAction { facing });
+                ctr.action_type(tuning.dash(facing));
             }
         }
 
         if keyboard.pressed(KeyCode::KeyL) {
             ctr.action_type(AttackAction);
         }
+
+        // one lunge per press, feeding it while held would chain them back to back
+        let lunge_pressed = keyboard.pressed(KeyCode::KeyK);
+        let lunge_just_pressed = lunge_pressed && !*lunge_held;
+        *lunge_held = lunge_pressed;
+        if lunge_just_pressed && !hanging && !swimming {
+            // lunge forward
+            ctr.action_type(ScriptedMotionAction::new(
+                MotionProfile::displacement(Vec3::NEG_Z * 4.),
+                0.25,
+            ));
+        }
+
+        if mouse.pressed(MouseButton::Right) && !hanging && !swimming {
+            if let Ok(camera) = camera_query.get_single() {
+                ctr.action_type(GrappleAction::new(camera.forward()));
+            }
+        }
     }
 }
@@ -1,10 +1,34 @@
-use bevy::prelude::*;
-use bevy_rapier3d::prelude::*;
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use super::{
+    backend::{
+        CharacterControllerBackend, RigidBodyTracker, RigidBodyVelocity, SensorShape,
+        SpatialQueryFilter,
+    },
+    CharacterController,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum ProximitySensorCast {
+    Ray,
+    /// Sweeps the shape along the sensor direction. The reported distance is how far the shape
+    /// travelled, so offset `origin` against the direction by the shape's extent to measure from
+    /// the character's center.
+    Shape(SensorShape),
+}
 
 #[derive(Debug, Component)]
 pub struct ProximitySensor {
+    /// Offset from the character's origin, in the gravity frame where up is Y.
     pub origin: Vec3,
+    /// Cast direction in the gravity frame, so the default `NEG_Y` follows gravity.
     pub direction: Vec3,
+    pub cast: ProximitySensorCast,
+    pub cast_range: f32,
+    pub filter: SpatialQueryFilter,
+    /// Whether other character controllers count as ground. Other dynamic bodies do unless
+    /// `filter` excludes them.
+    pub characters_as_ground: bool,
     pub output: Option<ProximitySensorOutput>,
 }
 
@@ -13,6 +37,13 @@ impl Default for ProximitySensor {
         Self {
             origin: Vec3::ZERO,
             direction: Vec3::NEG_Y,
+            cast: ProximitySensorCast::Ray,
+            cast_range: 3.,
+            filter: SpatialQueryFilter {
+                exclude_dynamic: false,
+                ..default()
+            },
+            characters_as_ground: true,
             output: None,
         }
     }
@@ -22,49 +53,71 @@ impl Default for ProximitySensor {
 pub struct ProximitySensorOutput {
     pub entity: Entity,
     pub distance: f32,
+    /// Where the sensor met the ground, below the character's center.
+    pub point: Vec3,
+    /// Surface normal at the hit point.
+    pub normal: Vec3,
+    /// Velocity of the ground at the hit point, and the angular velocity of its body.
+    pub ground_velocity: RigidBodyVelocity,
 }
 
-pub fn _cast_shape_system(
-    mut q: Query<(&mut ProximitySensor, &Transform, &Collider)>,
-    rapier_context: Res<RapierContext>,
+pub fn proximity_sensor_system<B: CharacterControllerBackend>(
+    mut q: Query<(Entity, &mut ProximitySensor, &Transform, &RigidBodyTracker)>,
+    characters: Query<(), With<CharacterController>>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
 ) {
-    for (mut sensor, transform, collider) in q.iter_mut() {
-        let cast_shape_result = rapier_context.cast_shape(
-            transform.translation + sensor.origin,
-            transform.rotation,
-            sensor.direction,
-            &collider,
-            1.5,
-            true,
-            QueryFilter::only_fixed().exclude_sensors(),
-        );
+    let spatial_query = B::spatial_query(&spatial_query);
 
-        sensor.output = match cast_shape_result {
-            None => None,
-            Some((entity, toi)) => Some(ProximitySensorOutput {
-                entity,
-                distance: toi.toi,
-            }),
-        }
-    }
-}
+    for (entity, mut sensor, transform, tracker) in q.iter_mut() {
+        let characters_as_ground = sensor.characters_as_ground;
+        let is_ground = |other: Entity| {
+            characters_as_ground || !characters.contains(spatial_query.rigid_body_of(other))
+        };
+        let filter = sensor.filter.excluding(entity);
 
-pub fn cast_ray_system(
-    mut q: Query<(&mut ProximitySensor, &Transform)>,
-    rapier_context: Res<RapierContext>,
-) {
-    for (mut sensor, transform) in q.iter_mut() {
-        let result = rapier_context.cast_ray(
-            transform.translation + sensor.origin,
-            sensor.direction,
-            3.,
-            false,
-            QueryFilter::only_fixed().exclude_sensors(),
-        );
+        let gravity_frame = tracker.gravity_frame();
+        let origin = transform.translation + gravity_frame * sensor.origin;
+        let direction = gravity_frame * sensor.direction;
+        let ray_hit = || {
+            spatial_query.cast_ray(
+                origin,
+                direction,
+                sensor.cast_range,
+                filter,
+                Some(&is_ground),
+            )
+        };
 
-        sensor.output = match result {
-            None => None,
-            Some((entity, distance)) => Some(ProximitySensorOutput { entity, distance }),
-        }
+        let hit = match sensor.cast {
+            ProximitySensorCast::Ray => ray_hit().map(|hit| (hit.entity, hit.distance, hit.normal)),
+            ProximitySensorCast::Shape(shape) => spatial_query
+                .cast_shape(
+                    shape,
+                    origin,
+                    transform.rotation,
+                    direction,
+                    sensor.cast_range,
+                    filter,
+                    Some(&is_ground),
+                )
+                .map(|hit| {
+                    // the swept shape may touch an edge the center ray misses
+                    let normal = ray_hit()
+                        .map(|ray_hit| ray_hit.normal)
+                        .unwrap_or(-direction);
+                    (hit.entity, hit.distance, normal)
+                }),
+        };
+
+        sensor.output = hit.map(|(hit_entity, distance, normal)| {
+            let point = origin + direction * distance;
+            ProximitySensorOutput {
+                entity: hit_entity,
+                distance,
+                point,
+                normal,
+                ground_velocity: spatial_query.point_velocity(hit_entity, point),
+            }
+        });
     }
 }
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use super::{
+    backend::RigidBodyTracker,
+    motion::{Motion, VelChange},
+    CharacterController,
+};
+
+/// Soft push between characters that come closer than their radii, so crowds spread out before
+/// their colliders grind against each other.
+#[derive(Debug, Component)]
+pub struct CharacterSeparation {
+    /// Keep-away radius around the character, usually a little wider than its collider.
+    pub radius: f32,
+    /// Acceleration per unit of overlap.
+    pub stiffness: f32,
+    /// Damping of the velocity at which the characters close in.
+    pub damping: f32,
+}
+
+impl Default for CharacterSeparation {
+    fn default() -> Self {
+        Self {
+            radius: 1.2,
+            stiffness: 40.,
+            damping: 4.,
+        }
+    }
+}
+
+/// Adds the separation push to the motion the controller worked out, along each character's
+/// ground plane so it never lifts anyone. Characters standing on each other are left alone.
+pub fn character_separation_system(
+    mut query: Query<(
+        Entity,
+        &Transform,
+        &RigidBodyTracker,
+        &CharacterController,
+        &CharacterSeparation,
+        &mut Motion,
+    )>,
+) {
+    let mut combinations = query.iter_combinations_mut();
+    while let Some([a, b]) = combinations.fetch_next() {
+        let (a_entity, a_transform, a_tracker, a_ctr, a_separation, mut a_motion) = a;
+        let (b_entity, b_transform, b_tracker, b_ctr, b_separation, mut b_motion) = b;
+
+        let stacked = a_ctr
+            .ground()
+            .is_some_and(|ground| ground.entity == b_entity)
+            || b_ctr
+                .ground()
+                .is_some_and(|ground| ground.entity == a_entity);
+        if stacked {
+            continue;
+        }
+
+        let radii = a_separation.radius + b_separation.radius;
+        let offset = b_transform.translation - a_transform.translation;
+        if offset.length() >= radii {
+            continue;
+        }
+        // only the sideways overlap is pushed apart, so one can rest just off-center on the other
+        let overlap = radii - offset.reject_from(Vec3::from(a_tracker.up())).length();
+        if overlap <= 0. {
+            continue;
+        }
+
+        let closing_speed = (a_tracker.velocity.linvel - b_tracker.velocity.linvel)
+            .dot(offset.normalize_or_zero())
+            .max(0.);
+        let stiffness = (a_separation.stiffness + b_separation.stiffness) * 0.5;
+        let damping = (a_separation.damping + b_separation.damping) * 0.5;
+        let push = overlap * stiffness + closing_speed * damping;
+
+        for (motion, tracker, away) in [
+            (&mut a_motion, a_tracker, -offset),
+            (&mut b_motion, b_tracker, offset),
+        ] {
+            let away = away
+                .reject_from(Vec3::from(tracker.up()))
+                .try_normalize()
+                // stacked exactly on top of each other, nothing sideways to push along
+                .unwrap_or(Vec3::ZERO);
+            motion.linvel += VelChange::accel(away * push);
+        }
+    }
+}
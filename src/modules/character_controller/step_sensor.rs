@@ -0,0 +1,102 @@
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use super::{
+    backend::{CharacterControllerBackend, RigidBodyTracker, SpatialQueryFilter},
+    CharacterController, WalkMotionType,
+};
+
+/// Looks for a step in front of a walking character's feet, in the direction it walks.
+#[derive(Debug, Component)]
+pub struct StepSensor {
+    /// How far in front of the character's center a step can be.
+    pub reach: f32,
+    pub filter: SpatialQueryFilter,
+    pub output: Option<StepSensorOutput>,
+}
+
+/// How far past the surface of the character's collider a step can be.
+const STEP_MARGIN: f32 = 0.7;
+
+impl Default for StepSensor {
+    /// Sized for a collider with a radius of 0.5.
+    fn default() -> Self {
+        Self {
+            reach: 0.5 + STEP_MARGIN,
+            filter: SpatialQueryFilter::default(),
+            output: None,
+        }
+    }
+}
+
+impl StepSensor {
+    /// A sensor that reaches just past a collider of `radius` around the character's center.
+    pub fn for_body_radius(radius: f32) -> Self {
+        Self {
+            reach: radius + STEP_MARGIN,
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepSensorOutput {
+    pub entity: Entity,
+    /// Height of the top of the step above the character's feet.
+    pub height: f32,
+    /// Point on top of the step.
+    pub point: Vec3,
+}
+
+/// Probes along the walk direction the character was last fed, from its feet up to the walk
+/// basis' `max_step_height`.
+pub fn step_sensor_system<B: CharacterControllerBackend>(
+    mut q: Query<(
+        Entity,
+        &mut StepSensor,
+        &Transform,
+        &RigidBodyTracker,
+        &CharacterController,
+    )>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
+) {
+    let spatial_query = B::spatial_query(&spatial_query);
+
+    for (entity, mut sensor, transform, tracker, ctr) in q.iter_mut() {
+        let filter = sensor.filter.excluding(entity);
+        let up = Vec3::from(tracker.up());
+
+        sensor.output = (|| {
+            let (walk, _) = ctr.basis::<WalkMotionType>()?;
+            let forward = walk.velocity.reject_from(up).try_normalize()?;
+            let feet = transform.translation - up * walk.floating_height;
+
+            // something in the way just above the feet...
+            let wall =
+                spatial_query.cast_ray(feet + up * 0.05, forward, sensor.reach, filter, None)?;
+            if wall.normal.dot(up) > 0.7 {
+                return None;
+            }
+
+            // ...that ends below the highest step
+            let above_step = feet + up * walk.max_step_height;
+            if spatial_query
+                .cast_ray(above_step, forward, sensor.reach, filter, None)
+                .is_some()
+            {
+                return None;
+            }
+
+            let over_step = above_step + forward * (wall.distance + 0.1);
+            let top = spatial_query.cast_ray(over_step, -up, walk.max_step_height, filter, None)?;
+            if top.normal.dot(up) < 0.7 {
+                return None;
+            }
+
+            Some(StepSensorOutput {
+                entity: top.entity,
+                height: walk.max_step_height - top.distance,
+                point: top.point,
+            })
+        })();
+    }
+}
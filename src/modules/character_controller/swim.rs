@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use super::{
+    liquid_sensor::LiquidSensor,
+    motion::{Motion, VelChange},
+    traits::basis::{Basis, BasisContext},
+    utils::ProjectionPlaneForRotation,
+    CharacterController, WalkMotionType,
+};
+
+#[derive(Debug, Default)]
+pub struct SwimState {
+    /// How far below the surface the character is.
+    pub depth: f32,
+    /// The character floats at the surface rather than being fully under water.
+    pub surfaced: bool,
+}
+
+#[derive(Copy, Clone)]
+pub struct SwimBasis {
+    /// Desired velocity. Unlike walking, the vertical component steers up and down.
+    pub velocity: Vec3,
+    pub facing: Option<Direction3d>,
+    pub acceleration: f32,
+    /// Linear drag applied to the difference between the current and desired velocity.
+    pub drag: f32,
+    /// Depth at which buoyancy cancels gravity, so the character floats there.
+    pub float_depth: f32,
+    /// Upper bound of the buoyancy, as a multiple of gravity.
+    pub max_buoyancy: f32,
+    /// Vertical speed `JumpAction` swims up with.
+    pub swim_up_speed: f32,
+    /// Depth at which a walking character starts swimming.
+    pub enter_depth: f32,
+    /// Depth under which a swimming character goes back to walking.
+    pub exit_depth: f32,
+    pub turning_angvel: f32,
+}
+
+impl Default for SwimBasis {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            facing: None,
+            acceleration: 20.,
+            drag: 2.,
+            float_depth: 0.8,
+            max_buoyancy: 2.,
+            swim_up_speed: 4.,
+            enter_depth: 1.0,
+            exit_depth: 0.5,
+            turning_angvel: 5.,
+        }
+    }
+}
+
+impl SwimBasis {
+    fn get_torque(&self, ctx: BasisContext) -> f32 {
+        let existing_angvel = ctx.velocity.angvel.dot(Vec3::from(ctx.up));
+        match self.facing {
+            None => -existing_angvel,
+            Some(facing) => {
+                let projection = ProjectionPlaneForRotation::from_up_using_default_forward(ctx.up);
+                let current_forward = ctx.transform.rotation.mul_vec3(projection.forward);
+                let rotation_along_up_axis =
+                    projection.rotation_to_set_forward(current_forward, Vec3::from(facing));
+
+                (rotation_along_up_axis / ctx.frame_duration)
+                    .clamp(-self.turning_angvel, self.turning_angvel)
+                    - existing_angvel
+            }
+        }
+    }
+}
+
+impl Basis for SwimBasis {
+    const NAME: &'static str = "Swim";
+
+    type State = SwimState;
+
+    fn apply(&self, state: &mut Self::State, ctx: BasisContext, motion: &mut Motion) {
+        state.depth = ctx
+            .liquid_sensor_output
+            .map(|output| output.depth)
+            .unwrap_or(0.);
+        state.surfaced = state.depth <= self.float_depth + 0.1;
+
+        let mut target_velocity = self.velocity;
+        if state.surfaced {
+            // steering up at the surface would only make the character bob
+            let up = Vec3::from(ctx.up);
+            target_velocity -= up * target_velocity.dot(up).max(0.);
+        }
+
+        let submersion = (state.depth / self.float_depth).clamp(0., self.max_buoyancy);
+        let buoyancy = VelChange::accel(-ctx.gravity * submersion);
+        let drag = VelChange::accel((target_velocity - ctx.velocity.linvel) * self.drag);
+        let steering = VelChange::boost(
+            (target_velocity - ctx.velocity.linvel)
+                .clamp_length_max(self.acceleration * ctx.frame_duration),
+        );
+
+        motion.linvel = steering + drag + buoyancy;
+        motion.angvel = VelChange::boost(ctx.up * self.get_torque(ctx));
+    }
+
+    fn is_airborne(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    fn violate_coyote_time(&self, _state: &mut Self::State) {}
+
+    fn displacement(&self, _state: &Self::State) -> Option<Vec3> {
+        None
+    }
+}
+
+/// Switches characters between walking and swimming as they enter and leave liquid volumes.
+pub fn liquid_switch_system(mut query: Query<(&mut CharacterController, &LiquidSensor)>) {
+    for (mut ctr, liquid_sensor) in query.iter_mut() {
+        let Some(basis_name) = ctr.basis_name() else {
+            continue;
+        };
+        let Some(swim) = ctr
+            .basis_mut::<SwimBasis>(SwimBasis::NAME)
+            .map(|swim| swim.input)
+        else {
+            continue;
+        };
+        let depth = liquid_sensor.output.map(|output| output.depth);
+
+        if basis_name == SwimBasis::NAME {
+            if depth.map_or(true, |depth| depth < swim.exit_depth) {
+                ctr.switch_basis(WalkMotionType::NAME);
+            }
+        } else if basis_name == WalkMotionType::NAME {
+            if depth.is_some_and(|depth| depth >= swim.enter_depth) {
+                ctr.switch_basis(SwimBasis::NAME);
+            }
+        }
+    }
+}
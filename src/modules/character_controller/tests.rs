@@ -0,0 +1,326 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{event::Events, schedule::ExecutorKind},
+    prelude::*,
+};
+
+use super::{
+    actions::{DoubleJumpAction, JumpAction},
+    backend::{
+        mock::{
+            mock_motor_system, mock_tracker_system, MockBackend, MockCollider, MockRigidBody,
+            MockWorld,
+        },
+        RigidBodyVelocity,
+    },
+    controller_system,
+    gravity::gravity_system,
+    ledge_sensor::ledge_sensor_system,
+    liquid_sensor::liquid_sensor_system,
+    proximity_sensor::proximity_sensor_system,
+    step_sensor::step_sensor_system,
+    traits::action::Action,
+    wall_sensor::wall_sensor_system,
+    ActionFinishedEvent, ActionInterruptedEvent, ActionRejectedEvent, ActionStartedEvent,
+    BasisChangedEvent, CharacterController, CharacterControllerBundle, LandedEvent, WalkMotionType,
+};
+
+const FRAME: f32 = 1. / 60.;
+
+/// A single character on the mock backend, stepped one frame at a time.
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    character: Entity,
+}
+
+impl Harness {
+    /// A character `height` above a wide floor whose top is at y = 0. It stands on the floor at
+    /// the walk basis' default floating height of 2.
+    fn new(height: f32) -> Self {
+        let mut world = World::new();
+        world.insert_resource(Time::<()>::default());
+        world.init_resource::<Events<ActionStartedEvent>>();
+        world.init_resource::<Events<ActionFinishedEvent>>();
+        world.init_resource::<Events<ActionInterruptedEvent>>();
+        world.init_resource::<Events<ActionRejectedEvent>>();
+        world.init_resource::<Events<BasisChangedEvent>>();
+        world.init_resource::<Events<LandedEvent>>();
+
+        let floor = world.spawn_empty().id();
+        world.insert_resource(MockWorld {
+            gravity: Vec3::NEG_Y * 9.81,
+            colliders: vec![MockCollider {
+                entity: floor,
+                center: Vec3::NEG_Y * 0.5,
+                half_extents: Vec3::new(50., 0.5, 50.),
+                velocity: RigidBodyVelocity::ZERO,
+                dynamic: false,
+                kinematic: false,
+                sensor: false,
+            }],
+        });
+
+        let character = world
+            .spawn((
+                CharacterControllerBundle::default(),
+                MockRigidBody {
+                    half_extents: Vec3::new(0.5, 1.5, 0.5),
+                    ..default()
+                },
+                Transform::from_xyz(0., height, 0.),
+            ))
+            .id();
+
+        // the controller pipeline of `CharacterControllerPlugin`, without its input and debug UI
+        let mut schedule = Schedule::default();
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        schedule.add_systems(
+            (
+                mock_tracker_system,
+                gravity_system::<MockBackend>,
+                (
+                    proximity_sensor_system::<MockBackend>,
+                    wall_sensor_system::<MockBackend>,
+                    ledge_sensor_system::<MockBackend>,
+                    step_sensor_system::<MockBackend>,
+                    liquid_sensor_system::<MockBackend>,
+                ),
+                controller_system::<MockBackend>,
+                mock_motor_system,
+            )
+                .chain(),
+        );
+
+        Self {
+            world,
+            schedule,
+            character,
+        }
+    }
+
+    fn ctr(&self) -> &CharacterController {
+        self.world
+            .get::<CharacterController>(self.character)
+            .unwrap()
+    }
+
+    fn velocity(&self) -> Vec3 {
+        self.world
+            .get::<MockRigidBody>(self.character)
+            .unwrap()
+            .velocity
+            .linvel
+    }
+
+    /// Runs one frame, feeding the walk basis and `jump` like the input systems would.
+    fn step(&mut self, jump: Option<JumpAction>) {
+        self.step_feeding(|ctr| {
+            if let Some(jump) = jump {
+                ctr.action_type(jump);
+            }
+        });
+    }
+
+    /// Runs one frame, feeding the walk basis and whatever `feed` feeds.
+    fn step_feeding(&mut self, feed: impl FnOnce(&mut CharacterController)) {
+        let mut ctr = self
+            .world
+            .get_mut::<CharacterController>(self.character)
+            .unwrap();
+        ctr.motion_type(WalkMotionType::default());
+        feed(&mut ctr);
+
+        self.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(FRAME));
+        self.schedule.run(&mut self.world);
+    }
+
+    fn step_for(&mut self, seconds: f32) {
+        for _ in 0..(seconds / FRAME).round() as usize {
+            self.step(None);
+        }
+    }
+
+    /// Steps until `condition` holds, for at most `seconds`. Returns whether it did.
+    fn step_until(
+        &mut self,
+        seconds: f32,
+        condition: impl Fn(&CharacterController) -> bool,
+    ) -> bool {
+        for _ in 0..(seconds / FRAME).round() as usize {
+            if condition(self.ctr()) {
+                return true;
+            }
+            self.step(None);
+        }
+        condition(self.ctr())
+    }
+
+    /// Takes the floor away, as if the character walked off a ledge.
+    fn remove_floor(&mut self) {
+        self.world.resource_mut::<MockWorld>().colliders.clear();
+    }
+}
+
+#[test]
+fn mock_character_stands_on_the_floor() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(1.);
+
+    let ctr = harness.ctr();
+    assert!(!ctr.is_airborne());
+    let ground = ctr.ground().expect("character should be on the floor");
+    assert!(
+        (ground.distance - 2.).abs() < 0.1,
+        "floating at {} instead of 2",
+        ground.distance
+    );
+}
+
+#[test]
+fn mock_character_goes_airborne_off_the_floor() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(0.5);
+
+    harness.remove_floor();
+    harness.step(None);
+    assert!(harness.ctr().ground().is_none());
+
+    harness.step_for(0.2);
+    assert!(harness.ctr().is_airborne());
+    assert!(harness.velocity().y < 0.);
+}
+
+#[test]
+fn mock_character_lands_after_falling() {
+    let mut harness = Harness::new(4.);
+    assert!(harness.step_until(0.5, |ctr| ctr.is_airborne()));
+    assert!(harness.step_until(2., |ctr| !ctr.is_airborne()));
+    assert!(harness.ctr().ground().is_some());
+}
+
+#[test]
+fn mock_character_starts_a_jump() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(0.5);
+
+    harness.step(Some(JumpAction::default()));
+    assert_eq!(harness.ctr().action_name(), Some(JumpAction::NAME));
+    assert!(harness.ctr().is_airborne());
+    assert!(harness.velocity().y > 5.);
+}
+
+#[test]
+fn buffered_jump_starts_on_landing() {
+    let mut harness = Harness::new(4.);
+    assert!(harness.step_until(0.5, |ctr| ctr.is_airborne()));
+
+    // pressed once in mid-air, then released
+    harness.step(Some(JumpAction {
+        input_buffer_time: 2.,
+        ..default()
+    }));
+    assert_eq!(harness.ctr().contender_name(), Some(JumpAction::NAME));
+    assert_eq!(harness.ctr().action_name(), None);
+
+    harness.step(None);
+    assert_eq!(harness.ctr().contender_name(), Some(JumpAction::NAME));
+    assert_eq!(harness.ctr().action_name(), None);
+
+    assert!(harness.step_until(2., |ctr| ctr.action_name() == Some(JumpAction::NAME)));
+    // started on the frame the character touched down
+    assert!(harness.ctr().ground().is_some());
+    assert!(harness.velocity().y > 0.);
+}
+
+#[test]
+fn buffered_jump_expires_after_input_buffer_time() {
+    let mut harness = Harness::new(10.);
+    assert!(harness.step_until(0.5, |ctr| ctr.is_airborne()));
+
+    harness.step(Some(JumpAction {
+        input_buffer_time: 0.2,
+        ..default()
+    }));
+    assert_eq!(harness.ctr().contender_name(), Some(JumpAction::NAME));
+
+    harness.step_for(0.25);
+    assert!(harness.ctr().is_airborne());
+    assert_eq!(harness.ctr().contender_name(), None);
+
+    assert!(harness.step_until(3., |ctr| !ctr.is_airborne()));
+    harness.step_for(0.1);
+    assert_eq!(harness.ctr().action_name(), None);
+}
+
+#[test]
+fn jump_is_allowed_within_coyote_time() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(0.5);
+
+    harness.remove_floor();
+    harness.step_for(0.05);
+    assert!(!harness.ctr().is_airborne());
+
+    harness.step(Some(JumpAction {
+        input_buffer_time: 0.,
+        ..default()
+    }));
+    assert_eq!(harness.ctr().action_name(), Some(JumpAction::NAME));
+    assert!(harness.velocity().y > 0.);
+}
+
+#[test]
+fn jump_is_rejected_after_coyote_time() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(0.5);
+
+    harness.remove_floor();
+    harness.step_for(0.2);
+    assert!(harness.ctr().is_airborne());
+
+    harness.step(Some(JumpAction {
+        input_buffer_time: 0.,
+        ..default()
+    }));
+    assert_eq!(harness.ctr().action_name(), None);
+    assert_eq!(harness.ctr().contender_name(), None);
+    assert!(harness.velocity().y < 0.);
+}
+
+#[test]
+fn double_jump_takes_over_a_rising_jump() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(0.5);
+
+    for _ in 0..6 {
+        harness.step(Some(JumpAction::default()));
+    }
+    assert_eq!(harness.ctr().action_name(), Some(JumpAction::NAME));
+    assert!(harness.velocity().y > 0.);
+
+    // a quick tap while still rising, fed on a single frame like a fresh press
+    harness.step_feeding(|ctr| {
+        ctr.action_type(JumpAction::default());
+        ctr.action_type(DoubleJumpAction::default());
+    });
+    assert_eq!(harness.ctr().action_name(), Some(DoubleJumpAction::NAME));
+    assert_eq!(harness.ctr().air_uses(DoubleJumpAction::NAME), 1);
+
+    harness.step(None);
+    assert_eq!(harness.ctr().action_name(), Some(DoubleJumpAction::NAME));
+    assert!(harness.velocity().y > 0.);
+}
+
+#[test]
+fn airtime_includes_coyote_time() {
+    let mut harness = Harness::new(2.);
+    harness.step_for(0.5);
+
+    harness.remove_floor();
+    assert!(harness.step_until(0.5, |ctr| ctr.is_airborne()));
+    assert!(harness.ctr().airtime() >= WalkMotionType::default().coyote_time);
+}
@@ -1,10 +1,16 @@
 use std::any::Any;
 
-use bevy::prelude::*;
-use bevy_rapier3d::prelude::*;
+use bevy::{prelude::*, time::Stopwatch};
 
 use crate::modules::character_controller::{
-    motion::Motion, proximity_sensor::ProximitySensorOutput,
+    backend::{RigidBodyVelocity, SpatialQuery},
+    cooldown::{ActionCooldown, ActionCooldownConfig},
+    ledge_sensor::LedgeSensorOutput,
+    liquid_sensor::LiquidSensorOutput,
+    motion::Motion,
+    proximity_sensor::ProximitySensorOutput,
+    step_sensor::StepSensorOutput,
+    wall_sensor::WallSensorOutput,
 };
 
 use super::basis::{Basis, BasisContext, BoxableBasis, DynamicBasis};
@@ -12,6 +18,8 @@ use super::basis::{Basis, BasisContext, BoxableBasis, DynamicBasis};
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum ActionInitiationDirective {
     Allow,
+    /// Keep the action as a contender and ask again next frame.
+    Delay,
     Reject,
 }
 
@@ -57,6 +65,12 @@ pub enum ActionLifecycleDirective {
 pub trait Action: 'static + Send + Sync {
     const NAME: &'static str;
 
+    /// Starting this action ends the coyote time of the current basis.
+    const VIOLATES_COYOTE_TIME: bool = false;
+
+    /// A contender with a higher priority than the current action interrupts it.
+    const PRIORITY: i32 = 0;
+
     type State: Default + Send + Sync;
 
     fn apply(
@@ -67,7 +81,26 @@ pub trait Action: 'static + Send + Sync {
         motion: &mut Motion,
     ) -> ActionLifecycleDirective;
 
-    fn initiation_decision(&self, ctx: ActionContext) -> ActionInitiationDirective;
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective;
+
+    /// Whether any contender may interrupt the action while it is in `state`, regardless of
+    /// priority.
+    fn can_be_cancelled(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    /// Called instead of `apply` on the frame the action is interrupted by a contender.
+    fn interrupt(&self, _state: &mut Self::State, _ctx: ActionContext, _motion: &mut Motion) {}
+
+    /// Charges the controller tracks for this action. A charge is spent every time it starts, and
+    /// the controller rejects the action while it has none left.
+    fn cooldown(&self) -> Option<ActionCooldownConfig> {
+        None
+    }
 }
 
 pub trait DynamicActionType: 'static + Send + Sync + Any {
@@ -85,7 +118,22 @@ pub trait DynamicActionType: 'static + Send + Sync + Any {
         motion: &mut Motion,
     ) -> ActionLifecycleDirective;
 
-    fn initiation_decision(&self, ctx: ActionContext) -> ActionInitiationDirective;
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective;
+
+    fn violates_coyote_time(&self) -> bool;
+
+    fn priority(&self) -> i32;
+
+    fn can_be_cancelled(&self) -> bool;
+
+    #[doc(hidden)]
+    fn interrupt(&mut self, ctx: ActionContext, motion: &mut Motion);
+
+    fn cooldown(&self) -> Option<ActionCooldownConfig>;
 }
 
 pub(crate) struct BoxableActionType<A: Action> {
@@ -120,19 +168,55 @@ impl<A: Action> DynamicActionType for BoxableActionType<A> {
         self.input.apply(&mut self.state, ctx, lifecycle, motion)
     }
 
-    fn initiation_decision(&self, ctx: ActionContext) -> ActionInitiationDirective {
-        self.input.initiation_decision(ctx)
+    fn initiation_decision(
+        &self,
+        ctx: ActionContext,
+        being_fed_for: &Stopwatch,
+    ) -> ActionInitiationDirective {
+        self.input.initiation_decision(ctx, being_fed_for)
+    }
+
+    fn violates_coyote_time(&self) -> bool {
+        A::VIOLATES_COYOTE_TIME
+    }
+
+    fn priority(&self) -> i32 {
+        A::PRIORITY
+    }
+
+    fn can_be_cancelled(&self) -> bool {
+        self.input.can_be_cancelled(&self.state)
+    }
+
+    fn interrupt(&mut self, ctx: ActionContext, motion: &mut Motion) {
+        self.input.interrupt(&mut self.state, ctx, motion)
+    }
+
+    fn cooldown(&self) -> Option<ActionCooldownConfig> {
+        self.input.cooldown()
     }
 }
 
 pub struct ActionContext<'a> {
     pub frame_duration: f32,
     pub proximity_sensor_output: Option<ProximitySensorOutput>,
+    pub wall_sensor_output: Option<WallSensorOutput>,
+    pub ledge_sensor_output: Option<LedgeSensorOutput>,
+    /// A step in front of a walking character.
+    pub step_sensor_output: Option<StepSensorOutput>,
+    /// The liquid volume the character is in, and how deep.
+    pub liquid_sensor_output: Option<LiquidSensorOutput>,
     pub transform: Transform,
-    pub velocity: Velocity,
+    pub velocity: RigidBodyVelocity,
     pub gravity: Vec3,
+    /// The character's local up, opposite to `gravity`.
+    pub up: Direction3d,
     pub motion_type: &'a dyn DynamicBasis,
-    pub rapier_context: &'a RapierContext,
+    pub spatial_query: &'a dyn SpatialQuery,
+    /// Charges left for the action being applied or considered, if it has a cooldown.
+    pub cooldown: Option<&'a ActionCooldown>,
+    /// Times the action started since the character last left the ground. Resets on landing.
+    pub air_uses: u32,
 }
 
 impl<'a> ActionContext<'a> {
@@ -146,8 +230,12 @@ impl<'a> ActionContext<'a> {
             frame_duration: self.frame_duration,
             velocity: self.velocity,
             proximity_sensor_output: self.proximity_sensor_output,
+            ledge_sensor_output: self.ledge_sensor_output,
+            step_sensor_output: self.step_sensor_output,
+            liquid_sensor_output: self.liquid_sensor_output,
             transform: self.transform,
             gravity: self.gravity,
+            up: self.up,
         }
     }
 }
@@ -1,19 +1,26 @@
 use std::any::Any;
 
 use bevy::prelude::*;
-use bevy_rapier3d::prelude::*;
 
 use crate::modules::character_controller::{
-    motion::Motion, proximity_sensor::ProximitySensorOutput,
+    backend::RigidBodyVelocity, ledge_sensor::LedgeSensorOutput, liquid_sensor::LiquidSensorOutput,
+    motion::Motion, proximity_sensor::ProximitySensorOutput, step_sensor::StepSensorOutput,
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct BasisContext {
     pub frame_duration: f32,
     pub proximity_sensor_output: Option<ProximitySensorOutput>,
+    pub ledge_sensor_output: Option<LedgeSensorOutput>,
+    /// A step in front of a walking character.
+    pub step_sensor_output: Option<StepSensorOutput>,
+    /// The liquid volume the character is in, and how deep.
+    pub liquid_sensor_output: Option<LiquidSensorOutput>,
     pub transform: Transform,
-    pub velocity: Velocity,
+    pub velocity: RigidBodyVelocity,
     pub gravity: Vec3,
+    /// The character's local up, opposite to `gravity`.
+    pub up: Direction3d,
 }
 
 pub trait Basis: 'static + Send + Sync {
@@ -26,6 +33,14 @@ pub trait Basis: 'static + Send + Sync {
     fn displacement(&self, state: &Self::State) -> Option<Vec3>;
 
     fn is_airborne(&self, state: &Self::State) -> bool;
+
+    /// Seconds since the character left the ground, including the coyote time during which it
+    /// does not count as airborne yet. Zero while on the ground.
+    fn ungrounded_time(&self, _state: &Self::State) -> f32 {
+        0.
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State);
 }
 
 pub trait DynamicBasis: Send + Sync + Any + 'static {
@@ -41,6 +56,10 @@ pub trait DynamicBasis: Send + Sync + Any + 'static {
     fn displacement(&self) -> Option<Vec3>;
 
     fn is_airborne(&self) -> bool;
+
+    fn ungrounded_time(&self) -> f32;
+
+    fn violate_coyote_time(&mut self);
 }
 
 pub(crate) struct BoxableBasis<B: Basis> {
@@ -77,4 +96,12 @@ impl<B: Basis> DynamicBasis for BoxableBasis<B> {
     fn is_airborne(&self) -> bool {
         self.input.is_airborne(&self.state)
     }
+
+    fn ungrounded_time(&self) -> f32 {
+        self.input.ungrounded_time(&self.state)
+    }
+
+    fn violate_coyote_time(&mut self) {
+        self.input.violate_coyote_time(&mut self.state)
+    }
 }
@@ -0,0 +1,213 @@
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::{BoxedFuture, HashSet},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    actions::{DashAction, JumpAction},
+    walk::SpringConfig,
+    ActionCooldownConfig, WalkMotionType,
+};
+
+/// Movement numbers of a character archetype, loaded from a `.movement.ron` file.
+///
+/// Characters point at the asset with [`MovementTuningHandle`], and get a copy of it as a
+/// component whenever it loads or changes on disk. Input and brains read that copy.
+#[derive(Asset, Component, TypePath, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementTuning {
+    pub walk: WalkTuning,
+    pub jump: JumpTuning,
+    pub dash: DashTuning,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalkTuning {
+    pub speed: f32,
+    pub floating_height: f32,
+    pub turning_angvel: f32,
+    pub spring_strength: f32,
+    pub spring_damper: f32,
+    pub coyote_time: f32,
+    pub air_acceleration: f32,
+    pub air_deceleration: f32,
+    pub max_air_speed: f32,
+    pub max_step_height: f32,
+    pub snap_distance: f32,
+}
+
+impl Default for WalkTuning {
+    fn default() -> Self {
+        let walk = WalkMotionType::default();
+        Self {
+            speed: 15.,
+            floating_height: walk.floating_height,
+            turning_angvel: walk.turning_angvel,
+            spring_strength: walk.spring_config.strength,
+            spring_damper: walk.spring_config.damper,
+            coyote_time: walk.coyote_time,
+            air_acceleration: walk.air_acceleration,
+            air_deceleration: walk.air_deceleration,
+            max_air_speed: walk.max_air_speed,
+            max_step_height: walk.max_step_height,
+            snap_distance: walk.snap_distance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JumpTuning {
+    pub height: f32,
+    pub min_height: f32,
+    pub shorten_extra_gravity: f32,
+    pub fall_extra_gravity: f32,
+    pub peak_hang_time: f32,
+    pub input_buffer_time: f32,
+}
+
+impl Default for JumpTuning {
+    fn default() -> Self {
+        let jump = JumpAction::default();
+        Self {
+            height: jump.height,
+            min_height: jump.min_height,
+            shorten_extra_gravity: jump.shorten_extra_gravity,
+            fall_extra_gravity: jump.fall_extra_gravity,
+            peak_hang_time: jump.peak_hang_time,
+            input_buffer_time: jump.input_buffer_time,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DashTuning {
+    pub speed: f32,
+    pub duration: f32,
+    pub cooldown: f32,
+    pub charges: u32,
+}
+
+impl Default for DashTuning {
+    fn default() -> Self {
+        let dash = DashAction::new(Direction3d::NEG_Z);
+        Self {
+            speed: dash.speed,
+            duration: dash.duration,
+            cooldown: dash.cooldown.duration,
+            charges: dash.cooldown.charges,
+        }
+    }
+}
+
+impl MovementTuning {
+    /// Walk basis moving along `direction` at the tuned speed.
+    pub fn walk(&self, direction: Vec3, facing: Option<Direction3d>) -> WalkMotionType {
+        WalkMotionType {
+            velocity: direction.normalize_or_zero() * self.walk.speed,
+            facing,
+            spring_config: SpringConfig {
+                strength: self.walk.spring_strength,
+                damper: self.walk.spring_damper,
+            },
+            floating_height: self.walk.floating_height,
+            turning_angvel: self.walk.turning_angvel,
+            coyote_time: self.walk.coyote_time,
+            air_acceleration: self.walk.air_acceleration,
+            air_deceleration: self.walk.air_deceleration,
+            max_air_speed: self.walk.max_air_speed,
+            max_step_height: self.walk.max_step_height,
+            snap_distance: self.walk.snap_distance,
+            ..default()
+        }
+    }
+
+    pub fn jump(&self) -> JumpAction {
+        JumpAction {
+            height: self.jump.height,
+            min_height: self.jump.min_height,
+            shorten_extra_gravity: self.jump.shorten_extra_gravity,
+            fall_extra_gravity: self.jump.fall_extra_gravity,
+            peak_hang_time: self.jump.peak_hang_time,
+            input_buffer_time: self.jump.input_buffer_time,
+        }
+    }
+
+    pub fn dash(&self, facing: Direction3d) -> DashAction {
+        DashAction {
+            facing,
+            speed: self.dash.speed,
+            duration: self.dash.duration,
+            cooldown: ActionCooldownConfig::new(self.dash.cooldown, self.dash.charges),
+        }
+    }
+}
+
+/// The movement tuning asset a character uses.
+#[derive(Debug, Component)]
+pub struct MovementTuningHandle(pub Handle<MovementTuning>);
+
+#[derive(Default)]
+pub struct MovementTuningLoader;
+
+#[derive(Debug, Error)]
+pub enum MovementTuningLoaderError {
+    #[error("could not read movement tuning: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse movement tuning: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for MovementTuningLoader {
+    type Asset = MovementTuning;
+    type Settings = ();
+    type Error = MovementTuningLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["movement.ron"]
+    }
+}
+
+/// Copies the tuning asset onto its characters when they get a new handle, when it finishes
+/// loading and when it is hot-reloaded.
+pub fn sync_movement_tuning_system(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<MovementTuning>>,
+    tunings: Res<Assets<MovementTuning>>,
+    query: Query<(Entity, Ref<MovementTuningHandle>)>,
+) {
+    let changed: HashSet<AssetId<MovementTuning>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, handle) in query.iter() {
+        if !handle.is_changed() && !changed.contains(&handle.0.id()) {
+            continue;
+        }
+        if let Some(tuning) = tunings.get(&handle.0) {
+            commands.entity(entity).insert(*tuning);
+        }
+    }
+}
@@ -13,8 +13,13 @@ impl ProjectionPlaneForRotation {
         }
     }
 
+    /// Uses -Z laid onto the plane perpendicular to `up`, or X when `up` is along Z.
     pub fn from_up_using_default_forward(up: Direction3d) -> Self {
-        Self::from_up_and_fowrard(up, Vec3::NEG_Z)
+        let forward = Vec3::NEG_Z
+            .reject_from(Vec3::from(up))
+            .try_normalize()
+            .unwrap_or(Vec3::X);
+        Self::from_up_and_fowrard(up, forward)
     }
 
     pub fn project_and_normalize(&self, vector: Vec3) -> Vec2 {
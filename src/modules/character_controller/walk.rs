@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{f32::consts::FRAC_PI_4, time::Duration};
 
 use bevy::prelude::*;
 
@@ -10,8 +10,8 @@ use super::{
 
 #[derive(Debug, Clone, Copy)]
 pub struct SpringConfig {
-    strength: f32,
-    damper: f32,
+    pub strength: f32,
+    pub damper: f32,
 }
 impl Default for SpringConfig {
     fn default() -> Self {
@@ -26,17 +26,62 @@ impl Default for SpringConfig {
 pub struct WalkMotionState {
     pub spring_force: f32,
     pub standing_offset: f32,
+    /// Normal of the ground the character is standing on, if any.
+    pub ground_normal: Option<Vec3>,
+    /// The ground is steeper than `max_slope_angle` and the character slides down.
+    pub sliding: bool,
+    /// Velocity of the ground while standing on it, kept while airborne so the character carries
+    /// the momentum of a moving platform it jumped off. Cleared on landing.
+    pub inherited_velocity: Vec3,
+    /// The ground is further than the spring reaches on its own, and the character is held on it.
+    pub snapped: bool,
+    /// Height of the step the character is climbing onto this frame.
+    pub step_height: Option<f32>,
     airborne_timer: Option<Timer>,
+    coyote_violated: bool,
+    up: Vec3,
+}
+
+/// What the character turns to face while walking.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FacingMode {
+    /// Face `facing`, usually the movement direction. Keeps the current heading when it is `None`.
+    #[default]
+    Movement,
+    /// Face a point in the world, strafing around it, e.g. a locked-on target.
+    LookAt(Vec3),
+    /// Face a fixed direction regardless of movement, e.g. the camera's forward.
+    Direction(Direction3d),
 }
 
 #[derive(Copy, Clone)]
 pub struct WalkMotionType {
     pub velocity: Vec3,
     pub facing: Option<Direction3d>,
+    pub facing_mode: FacingMode,
     pub spring_config: SpringConfig,
-    pub up: Direction3d,
     pub floating_height: f32,
     pub turning_angvel: f32,
+    /// How quickly the character rotates to stand along its local up when gravity turns.
+    pub upright_stiffness: f32,
+    /// Seconds after leaving the ground during which the character still counts as grounded.
+    pub coyote_time: f32,
+    /// Horizontal acceleration while airborne and steering, also used to brake momentum against
+    /// the input. Zero disables air control.
+    pub air_acceleration: f32,
+    /// Horizontal deceleration while airborne without input.
+    pub air_deceleration: f32,
+    /// Horizontal speed air control will not accelerate past. Faster momentum, e.g. from a dash
+    /// or a launch, is kept while the input points along it and braked at `air_acceleration`
+    /// when the input turns away from it.
+    pub max_air_speed: f32,
+    /// Steepest ground, in radians, the character can walk on without sliding down.
+    pub max_slope_angle: f32,
+    /// Tallest step, above the ground, the character climbs onto without jumping.
+    pub max_step_height: f32,
+    /// How far below `floating_height` grounded characters are pulled back to the ground, over
+    /// crests and small drops. Does nothing once coyote time is violated, e.g. by a jump.
+    pub snap_distance: f32,
 }
 
 impl Default for WalkMotionType {
@@ -44,46 +89,137 @@ impl Default for WalkMotionType {
         Self {
             velocity: Default::default(),
             facing: None,
+            facing_mode: FacingMode::Movement,
             spring_config: SpringConfig::default(),
-            up: Direction3d::Y,
             floating_height: 2.0,
             turning_angvel: 5.,
+            upright_stiffness: 10.,
+            coyote_time: 0.15,
+            air_acceleration: 0.,
+            air_deceleration: 0.,
+            max_air_speed: 15.,
+            max_slope_angle: FRAC_PI_4,
+            max_step_height: 0.6,
+            snap_distance: 0.5,
         }
     }
 }
 
 impl WalkMotionType {
-    fn calculate_spring_force(&self, ctx: BasisContext) -> f32 {
+    /// How far the spring reaches. Grounded characters snap to ground further down.
+    fn spring_range(&self, state: &WalkMotionState) -> f32 {
+        let snapping = state.airborne_timer.is_none() && !state.coyote_violated;
+        match snapping {
+            true => self.floating_height + self.snap_distance.max(0.1),
+            false => self.floating_height + 0.1,
+        }
+    }
+
+    fn calculate_spring_force(
+        &self,
+        state: &WalkMotionState,
+        ctx: BasisContext,
+        vertical_velocity: f32,
+        spring_range: f32,
+    ) -> f32 {
+        if state.coyote_violated {
+            return 0.;
+        }
+
         match ctx.proximity_sensor_output {
             None => 0.,
-            Some(output) => match output.distance > self.floating_height + 0.1 {
+            Some(output) => match output.distance > spring_range {
                 true => 0.,
                 false => {
                     let deviation = self.floating_height - output.distance;
                     (deviation * self.spring_config.strength)
-                        - (ctx.velocity.linvel.y * self.spring_config.damper)
+                        - (vertical_velocity * self.spring_config.damper)
                 }
             },
         }
     }
 
-    fn get_torque(&self, ctx: BasisContext) -> f32 {
-        let existing_angvel = ctx.velocity.angvel.dot(Vec3::from(self.up));
-        match self.facing {
-            None => -existing_angvel,
+    /// Walk velocity laid along the ground plane, keeping the requested horizontal speed.
+    fn velocity_along_ground(&self, up: Vec3, normal: Vec3, sliding: bool) -> Vec3 {
+        let mut velocity = self.velocity.reject_from(up);
+
+        if sliding {
+            // no walking up a slope that is too steep
+            let downhill = normal.reject_from(up).normalize_or_zero();
+            velocity -= downhill * velocity.dot(downhill).min(0.);
+            return velocity;
+        }
+
+        let normal_up = normal.dot(up);
+        if normal_up <= f32::EPSILON {
+            return velocity;
+        }
+        let along_plane = velocity - up * (velocity.dot(normal) / normal_up);
+        along_plane.normalize_or_zero() * velocity.length()
+    }
+
+    fn air_control(&self, ctx: BasisContext, inherited_velocity: Vec3) -> VelChange {
+        let up = Vec3::from(ctx.up);
+        let current_velocity = (ctx.velocity.linvel - inherited_velocity).reject_from(up);
+        if self.velocity == Vec3::ZERO {
+            let delta_velocity =
+                (-current_velocity).clamp_length_max(self.air_deceleration * ctx.frame_duration);
+            return VelChange::boost(delta_velocity);
+        }
+
+        let target_velocity = self
+            .velocity
+            .reject_from(up)
+            .clamp_length_max(self.max_air_speed);
+        // momentum beyond the target along the input is kept, everything else is steered toward it
+        let input_direction = target_velocity.normalize_or_zero();
+        let desired_velocity = input_direction
+            * current_velocity
+                .dot(input_direction)
+                .max(target_velocity.length());
+        VelChange::boost(
+            (desired_velocity - current_velocity)
+                .clamp_length_max(self.air_acceleration * ctx.frame_duration),
+        )
+    }
+
+    /// Direction to face in the plane perpendicular to up, according to `facing_mode`.
+    fn desired_facing(&self, ctx: BasisContext) -> Option<Vec3> {
+        let facing = match self.facing_mode {
+            FacingMode::Movement => Vec3::from(self.facing?),
+            FacingMode::LookAt(point) => point - ctx.transform.translation,
+            FacingMode::Direction(direction) => Vec3::from(direction),
+        };
+        facing.reject_from(Vec3::from(ctx.up)).try_normalize()
+    }
+
+    fn get_torque(&self, ctx: BasisContext, ground_angvel: f32) -> f32 {
+        let existing_angvel = ctx.velocity.angvel.dot(Vec3::from(ctx.up));
+        match self.desired_facing(ctx) {
+            None => ground_angvel - existing_angvel,
             Some(facing) => {
-                let projection = ProjectionPlaneForRotation::from_up_using_default_forward(self.up);
+                let projection = ProjectionPlaneForRotation::from_up_using_default_forward(ctx.up);
                 let current_forward = ctx.transform.rotation.mul_vec3(projection.forward);
                 let rotation_along_up_axis =
-                    projection.rotation_to_set_forward(current_forward, Vec3::from(facing));
+                    projection.rotation_to_set_forward(current_forward, facing);
 
                 let desired_angvel = (rotation_along_up_axis / ctx.frame_duration)
                     .clamp(-self.turning_angvel, self.turning_angvel);
 
-                desired_angvel - existing_angvel
+                desired_angvel + ground_angvel - existing_angvel
             }
         }
     }
+
+    /// Angular velocity change that tilts the character's Y axis towards the local up and damps
+    /// any other tilting.
+    fn get_upright_torque(&self, ctx: BasisContext) -> Vec3 {
+        let up = Vec3::from(ctx.up);
+        let current_up = ctx.transform.rotation.mul_vec3(Vec3::Y);
+        let tilt_angvel = ctx.velocity.angvel.reject_from(up);
+
+        current_up.cross(up) * self.upright_stiffness - tilt_angvel
+    }
 }
 
 impl Basis for WalkMotionType {
@@ -96,45 +232,103 @@ impl Basis for WalkMotionType {
             timer.tick(Duration::from_secs_f32(ctx.frame_duration));
         }
 
+        let up = Vec3::from(ctx.up);
+        state.up = up;
+        let ground_velocity = ctx
+            .proximity_sensor_output
+            .map(|output| output.ground_velocity.linvel)
+            .unwrap_or(Vec3::ZERO);
+        let spring_range = self.spring_range(state);
+        let in_spring_range = ctx
+            .proximity_sensor_output
+            .is_some_and(|output| output.distance <= spring_range);
+        let rising = (ctx.velocity.linvel - ground_velocity).dot(up) > 0.;
+
         match (&mut state.airborne_timer, &ctx.proximity_sensor_output) {
             (Some(_), Some(sensor_output)) => {
-                if sensor_output.distance <= self.floating_height + 0.01 {
+                let can_land = !(state.coyote_violated && rising);
+                if can_land && sensor_output.distance <= self.floating_height + 0.01 {
                     state.airborne_timer = None;
+                    state.coyote_violated = false;
+                    state.inherited_velocity = Vec3::ZERO;
                 }
             }
             (Some(_), None) => {}
-            (None, None) => {
-                state.airborne_timer =
-                    Some(Timer::new(Duration::from_millis(150), TimerMode::Once));
+            (None, _) if !in_spring_range => {
+                state.airborne_timer = Some(Timer::from_seconds(self.coyote_time, TimerMode::Once));
             }
             (None, Some(sensor_output)) => {
                 state.standing_offset = sensor_output.distance - self.floating_height
             }
+            (None, None) => {}
+        }
+
+        // ground
+        let ground = ctx
+            .proximity_sensor_output
+            .filter(|_| in_spring_range && !self.is_airborne(state));
+        state.ground_normal = ground.map(|output| output.normal);
+        if let Some(ground) = ground {
+            state.inherited_velocity = ground.ground_velocity.linvel;
         }
+        state.sliding =
+            ground.is_some_and(|output| output.normal.angle_between(up) > self.max_slope_angle);
+        state.snapped = ground.is_some_and(|output| output.distance > self.floating_height + 0.1);
+        state.step_height = ctx
+            .step_sensor_output
+            .map(|step| step.height)
+            .filter(|height| {
+                ground.is_some() && self.velocity != Vec3::ZERO && *height <= self.max_step_height
+            });
 
         // horizontal movement
-        let delta_velocity = (self.velocity - ctx.velocity.linvel).reject_from(Vec3::from(self.up));
-        let target_velocity = match self.velocity == Vec3::ZERO {
-            true => VelChange::boost(delta_velocity),
-            false => VelChange::accel(delta_velocity),
+        let walk_velocity = match state.ground_normal {
+            Some(normal) => self.velocity_along_ground(up, normal, state.sliding),
+            None => self.velocity,
+        };
+        let delta_velocity =
+            (walk_velocity + ground_velocity - ctx.velocity.linvel).reject_from(up);
+        let target_velocity = match (state.ground_normal, state.sliding) {
+            (Some(normal), true) => {
+                let downhill = normal.reject_from(up).normalize_or_zero();
+                let steering = delta_velocity - downhill * delta_velocity.dot(downhill);
+                let slide = ctx.gravity.reject_from(normal).reject_from(up);
+                VelChange::accel(steering + slide)
+            }
+            _ => match self.velocity == Vec3::ZERO {
+                true => VelChange::boost(delta_velocity),
+                false => VelChange::accel(delta_velocity),
+            },
         };
         let horizontal_change = if !self.is_airborne(state) {
             target_velocity
         } else {
-            VelChange::ZERO
+            self.air_control(ctx, state.inherited_velocity)
         };
 
         // vertical movement
-        let spring_force = self.calculate_spring_force(ctx);
-        let vertical_change = VelChange::boost(self.up * spring_force);
+        let vertical_velocity = (ctx.velocity.linvel - ground_velocity - walk_velocity).dot(up);
+        let spring_force = match state.step_height {
+            // hop onto the step, as the spring measures from the lower ground until over it
+            Some(height) => {
+                let step_speed = (2. * ctx.gravity.length() * height).sqrt();
+                (step_speed - (ctx.velocity.linvel - ground_velocity).dot(up)).max(0.)
+            }
+            None => self.calculate_spring_force(state, ctx, vertical_velocity, spring_range),
+        };
+        let vertical_change = VelChange::boost(up * spring_force);
 
         motion.linvel = horizontal_change + vertical_change;
 
         let angular_change = if !self.is_airborne(state) {
-            VelChange::boost(self.get_torque(ctx) * Vec3::from(self.up))
+            let ground_angvel = ground
+                .map(|output| output.ground_velocity.angvel.dot(up))
+                .unwrap_or(0.);
+            VelChange::boost(self.get_torque(ctx, ground_angvel) * up)
         } else {
-            VelChange::boost(-ctx.velocity.angvel)
+            VelChange::boost(-ctx.velocity.angvel.project_onto(up))
         };
+        let angular_change = angular_change + VelChange::boost(self.get_upright_torque(ctx));
         motion.angvel = angular_change;
 
         // update state
@@ -142,15 +336,30 @@ impl Basis for WalkMotionType {
     }
 
     fn is_airborne(&self, state: &Self::State) -> bool {
+        state.coyote_violated
+            || state
+                .airborne_timer
+                .as_ref()
+                .is_some_and(|timer| timer.finished())
+    }
+
+    fn ungrounded_time(&self, state: &Self::State) -> f32 {
         state
             .airborne_timer
             .as_ref()
-            .is_some_and(|timer| timer.finished())
+            .map_or(0., |timer| timer.elapsed_secs())
+    }
+
+    fn violate_coyote_time(&self, state: &mut Self::State) {
+        state.coyote_violated = true;
+        if state.airborne_timer.is_none() {
+            state.airborne_timer = Some(Timer::from_seconds(self.coyote_time, TimerMode::Once));
+        }
     }
 
     fn displacement(&self, state: &Self::State) -> Option<Vec3> {
         match state.airborne_timer {
-            None => Some(Vec3::Y * state.standing_offset),
+            None => Some(state.up * state.standing_offset),
             Some(_) => None,
         }
     }
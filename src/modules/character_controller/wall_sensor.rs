@@ -0,0 +1,96 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
+
+use super::backend::{CharacterControllerBackend, RigidBodyTracker, SpatialQueryFilter};
+
+/// Casts a fan of rays perpendicular to the character's up and reports the closest wall.
+#[derive(Debug, Component)]
+pub struct WallSensor {
+    /// Offset from the character's origin, in the gravity frame where up is Y.
+    pub origin: Vec3,
+    pub ray_count: usize,
+    /// Measured from `origin`, so it has to reach past the character's own collider.
+    pub cast_range: f32,
+    /// Hits whose normal has a larger component along up than this are floors or ceilings.
+    pub max_normal_up: f32,
+    pub filter: SpatialQueryFilter,
+    pub output: Option<WallSensorOutput>,
+}
+
+/// How far past the surface of the character's collider walls are sensed.
+const WALL_MARGIN: f32 = 0.3;
+
+impl Default for WallSensor {
+    /// Sized for a collider with a radius of 0.5.
+    fn default() -> Self {
+        Self {
+            origin: Vec3::ZERO,
+            ray_count: 8,
+            cast_range: 0.5 + WALL_MARGIN,
+            max_normal_up: 0.3,
+            filter: SpatialQueryFilter::default(),
+            output: None,
+        }
+    }
+}
+
+impl WallSensor {
+    /// A sensor that reaches just past a collider of `radius` around the character's center.
+    pub fn for_body_radius(radius: f32) -> Self {
+        Self {
+            cast_range: radius + WALL_MARGIN,
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WallSensorOutput {
+    pub entity: Entity,
+    pub distance: f32,
+    pub point: Vec3,
+    /// Wall normal, pointing away from the wall.
+    pub normal: Vec3,
+}
+
+pub fn wall_sensor_system<B: CharacterControllerBackend>(
+    mut q: Query<(Entity, &mut WallSensor, &Transform, &RigidBodyTracker)>,
+    spatial_query: StaticSystemParam<B::SpatialQuery>,
+) {
+    let spatial_query = B::spatial_query(&spatial_query);
+
+    for (entity, mut sensor, transform, tracker) in q.iter_mut() {
+        let gravity_frame = tracker.gravity_frame();
+        let up = Vec3::from(tracker.up());
+        let origin = transform.translation + gravity_frame * sensor.origin;
+        let filter = sensor.filter.excluding(entity);
+
+        let mut closest: Option<WallSensorOutput> = None;
+        for i in 0..sensor.ray_count {
+            let angle = TAU * i as f32 / sensor.ray_count as f32;
+            let direction = gravity_frame * Quat::from_rotation_y(angle) * Vec3::NEG_Z;
+
+            let Some(hit) =
+                spatial_query.cast_ray(origin, direction, sensor.cast_range, filter, None)
+            else {
+                continue;
+            };
+
+            if hit.normal.dot(up).abs() > sensor.max_normal_up {
+                continue;
+            }
+
+            if closest.map_or(true, |wall| hit.distance < wall.distance) {
+                closest = Some(WallSensorOutput {
+                    entity: hit.entity,
+                    distance: hit.distance,
+                    point: hit.point,
+                    normal: hit.normal,
+                });
+            }
+        }
+
+        sensor.output = closest;
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::modules::character_controller::LandedEvent;
+
+use super::DamageTakenEvent;
+
+/// Damage a character takes from hard landings, rising along a curve from nothing at
+/// `safe_speed` to `max_damage` at `lethal_speed`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FallDamage {
+    /// Highest impact speed that does no damage.
+    pub safe_speed: f32,
+    /// Impact speed that does `max_damage`. Faster impacts do no more.
+    pub lethal_speed: f32,
+    pub max_damage: i32,
+    /// Shape of the curve between the two speeds: 1 is linear, higher values spare moderate
+    /// falls and punish the long ones.
+    pub exponent: f32,
+}
+
+impl Default for FallDamage {
+    fn default() -> Self {
+        Self {
+            safe_speed: 14.,
+            lethal_speed: 30.,
+            max_damage: 20,
+            exponent: 2.,
+        }
+    }
+}
+
+impl FallDamage {
+    pub fn damage(&self, impact_speed: f32) -> i32 {
+        if impact_speed <= self.safe_speed {
+            return 0;
+        }
+        let t = ((impact_speed - self.safe_speed)
+            / (self.lethal_speed - self.safe_speed).max(0.01))
+        .min(1.);
+        (t.powf(self.exponent) * self.max_damage as f32).ceil() as i32
+    }
+}
+
+pub fn fall_damage_system(
+    mut ev_landed: EventReader<LandedEvent>,
+    mut ev_damage: EventWriter<DamageTakenEvent>,
+    query: Query<&FallDamage>,
+) {
+    for ev in ev_landed.read() {
+        let Ok(fall_damage) = query.get(ev.entity) else {
+            continue;
+        };
+        let damage = fall_damage.damage(ev.impact_speed);
+        if damage > 0 {
+            ev_damage.send(DamageTakenEvent(damage, ev.entity));
+        }
+    }
+}
@@ -1,15 +1,26 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    prelude::*,
+};
 
-use crate::{app_state::AppState, world3d::Targetable};
+use crate::{
+    app_state::AppState,
+    modules::character_controller::{
+        actions::HitStunAction, CharacterController, UserControlsSystemSet,
+    },
+    world3d::Targetable,
+};
 
 use self::{
     attack::{AttackEvent, AttackPlugin},
     combat_stats::Stats,
+    fall_damage::fall_damage_system,
     status_effect::sprint::SprintPlugin,
 };
 
 pub mod attack;
 pub mod combat_stats;
+pub mod fall_damage;
 pub mod hitbox_bundle;
 pub mod status_effect;
 
@@ -30,6 +41,20 @@ fn handle_damage_taken(
     }
 }
 
+fn stun_on_damage(
+    mut ev_damage: EventReader<DamageTakenEvent>,
+    mut character_query: Query<&mut CharacterController>,
+) {
+    for ev in ev_damage.read() {
+        if ev.0 <= 0 {
+            continue;
+        }
+        if let Ok(mut ctr) = character_query.get_mut(ev.1) {
+            ctr.action_type(HitStunAction::default());
+        }
+    }
+}
+
 fn handle_health_change(
     mut ev_death: EventWriter<CharacterDeathEvent>,
     character_query: Query<(Entity, &Stats), Changed<Stats>>,
@@ -70,7 +95,25 @@ fn log_combat(
     }
 }
 
-pub struct CombatPlugin;
+/// Combat bookkeeping runs in `Update`. Hit stun is fed to the character controller, so it runs in
+/// the controller's schedule.
+pub struct CombatPlugin {
+    controller_schedule: InternedScheduleLabel,
+}
+
+impl CombatPlugin {
+    pub fn new(controller_schedule: impl ScheduleLabel) -> Self {
+        Self {
+            controller_schedule: controller_schedule.intern(),
+        }
+    }
+}
+
+impl Default for CombatPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
 
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
@@ -80,12 +123,19 @@ impl Plugin for CombatPlugin {
             .add_systems(
                 Update,
                 (
+                    fall_damage_system,
                     handle_damage_taken,
                     handle_health_change,
                     handle_death,
                     log_combat,
                 )
                     .run_if(in_state(AppState::Game)),
+            )
+            .add_systems(
+                self.controller_schedule,
+                stun_on_damage
+                    .in_set(UserControlsSystemSet)
+                    .run_if(in_state(AppState::Game)),
             );
     }
 }
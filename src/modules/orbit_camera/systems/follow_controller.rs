@@ -1,16 +1,27 @@
 use bevy::prelude::*;
 
+use crate::modules::character_controller::TransformInterpolation;
+
 use super::super::OrbitCamera;
 
 pub fn follow_controller(
-    transform_query: Query<&Transform>,
+    time: Res<Time<Fixed>>,
+    transform_query: Query<(&Transform, Option<&TransformInterpolation>)>,
     mut camera_query: Query<&mut OrbitCamera>,
 ) {
     for mut camera in camera_query.iter_mut() {
         match camera.subject {
             Some(e) => {
-                if let Ok(transform) = transform_query.get(e) {
-                    camera.center = camera.center.lerp(transform.translation + Vec3::Y, 0.1);
+                if let Ok((transform, interpolation)) = transform_query.get(e) {
+                    // follow what is rendered rather than the last physics step
+                    let translation = interpolation
+                        .and_then(|interpolation| {
+                            interpolation.interpolated(time.overstep_fraction())
+                        })
+                        .map_or(transform.translation, |interpolated| {
+                            interpolated.translation
+                        });
+                    camera.center = camera.center.lerp(translation + Vec3::Y, 0.1);
                 }
             }
             None => return,